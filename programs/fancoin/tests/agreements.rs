@@ -0,0 +1,77 @@
+mod common;
+
+use common::*;
+use fancoin::{GroupingStrategy, MAX_MINTING_AGREEMENTS};
+use solana_sdk::{instruction::Instruction, signature::Keypair};
+
+const GAME: u32 = 1;
+/// Players per `submit_minting_list` transaction
+const BATCH: usize = 3;
+
+/// Two validators in every player's group and `players` joined players, past the seed of
+/// epoch 0.
+async fn crowded_game(env: &mut TestEnv, players: usize) -> ([Keypair; 2], Vec<Player>, Keypair) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let mut joined = Vec::new();
+    for i in 0..players {
+        let player = env.register_player(&format!("player{}", i)).await;
+        env.join_game(GAME, &player).await;
+        joined.push(player);
+    }
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    (validators, joined, server)
+}
+
+fn prune_ix() -> Instruction {
+    let accounts = fancoin::accounts::PruneExpiredAgreements { game: game_pda(GAME) };
+    instruction(accounts, fancoin::instruction::PruneExpiredAgreements { game_number: GAME })
+}
+
+async fn open_agreements(env: &mut TestEnv) -> Vec<u64> {
+    env.game(GAME).await.minting_agreements.iter().map(|a| a.player_id).collect()
+}
+
+#[tokio::test]
+async fn a_full_game_skips_new_players_instead_of_failing() {
+    let mut env = TestEnv::start().await;
+    let (validators, players, server) = crowded_game(&mut env, MAX_MINTING_AGREEMENTS + 1).await;
+    let (open, extra) = players.split_at(MAX_MINTING_AGREEMENTS);
+    for batch in open.chunks(BATCH) {
+        let batch: Vec<&Player> = batch.iter().collect();
+        env.submit(GAME, 0, &server, &validators[0], &batch).await.unwrap();
+    }
+    assert_eq!(open_agreements(&mut env).await.len(), MAX_MINTING_AGREEMENTS);
+
+    // The extra player gets no agreement, but the rest of the list still counts
+    env.submit(GAME, 0, &server, &validators[1], &[&extra[0], &open[0]]).await.unwrap();
+    let game = env.game(GAME).await;
+    assert!(!open_agreements(&mut env).await.contains(&extra[0].id));
+    assert_eq!(game.pending_payouts.len(), 1);
+    assert_eq!(game.pending_payouts[0].player_id, open[0].id);
+}
+
+#[tokio::test]
+async fn expired_agreements_make_room_for_new_ones() {
+    let mut env = TestEnv::start().await;
+    let (validators, players, server) = crowded_game(&mut env, MAX_MINTING_AGREEMENTS + 1).await;
+    let (open, extra) = players.split_at(MAX_MINTING_AGREEMENTS);
+    for batch in open.chunks(BATCH) {
+        let batch: Vec<&Player> = batch.iter().collect();
+        env.submit(GAME, 0, &server, &validators[0], &batch).await.unwrap();
+    }
+
+    // Still open while within the TTL
+    let ttl = env.game(GAME).await.agreement_ttl_epochs;
+    let before_expiry = env.epoch_start(GAME, ttl).await - 1;
+    env.set_time(before_expiry).await;
+    env.send(&[prune_ix()], &[]).await.unwrap();
+    assert_eq!(open_agreements(&mut env).await.len(), MAX_MINTING_AGREEMENTS);
+
+    // Submitting into a full game prunes the expired agreements without the crank
+    env.seed_round(ttl, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    env.submit(GAME, ttl, &server, &validators[0], &[&extra[0]]).await.unwrap();
+    assert_eq!(open_agreements(&mut env).await, [extra[0].id]);
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy};
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const GAME: u32 = 1;
+
+/// Game with two validators in every player's group, alice joined, past its seed.
+async fn minting_game(env: &mut TestEnv) -> ([Keypair; 2], Player, Keypair) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    (validators, alice, server)
+}
+
+#[tokio::test]
+async fn approvals_need_a_registered_servers_attestation_for_this_epoch() {
+    let mut env = TestEnv::start().await;
+    let (validators, alice, server) = minting_game(&mut env).await;
+    let validator = &validators[0];
+
+    let impostor = Keypair::new();
+    let ixs = TestEnv::submit_ixs(GAME, 0, &impostor, &validator.pubkey(), &[&alice]);
+    assert_error(env.send(&ixs, &[validator]).await, ErrorCode::InvalidAttestation);
+
+    let stale = TestEnv::submit_ixs(GAME, 1, &server, &validator.pubkey(), &[&alice]);
+    assert_error(env.send(&stale, &[validator]).await, ErrorCode::InvalidAttestation);
+
+    // The signed score must be the submitted one
+    let mut ixs = TestEnv::submit_ixs(GAME, 0, &server, &validator.pubkey(), &[&alice]);
+    let message = fancoin::attestation_message(GAME, 0, &alice.name, 2);
+    let signature = server.sign_message(&message).as_ref().to_vec();
+    ixs[0] = ed25519_attestations(&[(server.pubkey(), signature, message)]);
+    assert_error(env.send(&ixs, &[validator]).await, ErrorCode::InvalidAttestation);
+
+    let without_precompile = TestEnv::submit_ixs(GAME, 0, &server, &validator.pubkey(), &[&alice]);
+    assert_error(env.send(&without_precompile[1..], &[validator]).await, ErrorCode::InvalidAttestation);
+    assert!(env.game(GAME).await.minting_agreements.is_empty());
+}
+
+#[tokio::test]
+async fn one_attestation_serves_every_validator() {
+    let mut env = TestEnv::start().await;
+    let (validators, alice, server) = minting_game(&mut env).await;
+
+    // Both validators relay the same server signature; each adds only its own approval
+    let first = TestEnv::submit_ixs(GAME, 0, &server, &validators[0].pubkey(), &[&alice]);
+    let second = TestEnv::submit_ixs(GAME, 0, &server, &validators[1].pubkey(), &[&alice]);
+    assert_eq!(first[0].data, second[0].data);
+    env.send(&first, &[&validators[0]]).await.unwrap();
+    assert_eq!(env.game(GAME).await.minting_agreements[0].validators, [validators[0].pubkey()]);
+
+    env.send(&second, &[&validators[1]]).await.unwrap();
+    let payout = env.game(GAME).await.pending_payouts[0].clone();
+    assert_eq!(payout.player_id, alice.id);
+    assert_eq!(payout.validators, [validators[0].pubkey(), validators[1].pubkey()]);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, PlayerAuthorityIndex, PlayerPda};
+use solana_program_test::BanksClientError;
+use solana_sdk::{
+    native_token::LAMPORTS_PER_SOL, pubkey::Pubkey, signature::Keypair, signer::Signer, system_instruction,
+    system_program,
+};
+
+async fn set_max_players(env: &mut TestEnv, max_players: u8) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::UpdateDapp { dapp: dapp_pda(), signer: env.owner.pubkey() };
+    let args = fancoin::instruction::SetMaxPlayersPerAuthority { max_players };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+async fn move_player(
+    env: &mut TestEnv,
+    player: &Player,
+    authority: &Keypair,
+    new_authority: &Pubkey,
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::UpdatePlayerAuthority {
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        old_authority_index: authority_index_pda(&authority.pubkey()),
+        new_authority_index: authority_index_pda(new_authority),
+        user: authority.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::UpdatePlayerAuthority { new_authority: *new_authority };
+    env.send(&[instruction(accounts, args)], &[authority]).await
+}
+
+#[tokio::test]
+async fn authorities_hold_a_bounded_number_of_players() {
+    let mut env = TestEnv::start().await;
+    let authority = Keypair::new();
+    env.try_register_player("alice", &authority).await.unwrap();
+    assert_error(
+        env.try_register_player("bob", &authority).await,
+        ErrorCode::TooManyPlayersForAuthority,
+    );
+
+    assert_error(
+        set_max_players(&mut env, fancoin::MAX_PLAYERS_PER_AUTHORITY as u8 + 1).await,
+        ErrorCode::InvalidRange,
+    );
+    set_max_players(&mut env, 2).await.unwrap();
+    env.try_register_player("bob", &authority).await.unwrap();
+
+    let index: PlayerAuthorityIndex = env.account(&authority_index_pda(&authority.pubkey())).await;
+    assert_eq!(index.player_indices, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn authority_changes_move_the_reverse_index() {
+    let mut env = TestEnv::start().await;
+    let alice = env.register_player("alice").await;
+    let bob = env.register_player("bob").await;
+    let stranger = Keypair::new();
+    // Authorities pay for the new reverse index
+    for authority in [&alice.authority, &bob.authority] {
+        let transfer = system_instruction::transfer(&env.owner.pubkey(), &authority.pubkey(), LAMPORTS_PER_SOL);
+        env.send(&[transfer], &[]).await.unwrap();
+    }
+
+    // Only the current authority may hand a player over
+    let accounts = fancoin::accounts::UpdatePlayerAuthority {
+        dapp: dapp_pda(),
+        player_pda: player_pda(alice.index),
+        old_authority_index: authority_index_pda(&bob.authority.pubkey()),
+        new_authority_index: authority_index_pda(&stranger.pubkey()),
+        user: bob.authority.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::UpdatePlayerAuthority { new_authority: stranger.pubkey() };
+    assert_error(env.send(&[instruction(accounts, args)], &[&bob.authority]).await, ErrorCode::Unauthorized);
+
+    // Bob's authority is at the limit of one player
+    assert_error(
+        move_player(&mut env, &alice, &alice.authority, &bob.authority.pubkey()).await,
+        ErrorCode::TooManyPlayersForAuthority,
+    );
+
+    move_player(&mut env, &alice, &alice.authority, &stranger.pubkey()).await.unwrap();
+    let player: PlayerPda = env.account(&player_pda(alice.index)).await;
+    assert_eq!(player.authority, stranger.pubkey());
+    let index: PlayerAuthorityIndex = env.account(&authority_index_pda(&stranger.pubkey())).await;
+    assert_eq!(index.player_indices, vec![alice.index]);
+    // The emptied index is closed
+    assert!(!env.exists(&authority_index_pda(&alice.authority.pubkey())).await);
+}
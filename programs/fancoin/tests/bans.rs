@@ -0,0 +1,139 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, PlayerBan, PlayerGameState};
+use solana_program_test::BanksClientError;
+use solana_sdk::{signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+/// Game made by `creator` with two validators in every player's group and alice and bob
+/// joined, past its seed.
+async fn moderated_game(env: &mut TestEnv, creator: &Keypair) -> ([Keypair; 2], [Player; 2], Keypair) {
+    env.create_game_for(GAME, &creator.pubkey()).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let players = [env.register_player("alice").await, env.register_player("bob").await];
+    for player in &players {
+        env.join_game(GAME, player).await;
+    }
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    (validators, players, server)
+}
+
+async fn ban(
+    env: &mut TestEnv,
+    signer: &Keypair,
+    player: &Player,
+    expires_at: Option<i64>,
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::BanPlayer {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        player_game_state: player_game_pda(GAME, player.id),
+        ban: player_ban_pda(GAME, player.id),
+        signer: signer.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::BanPlayer { game_number: GAME, player_index: player.index, reason_code: 7, expires_at };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+async fn unban(env: &mut TestEnv, signer: &Keypair, player: &Player) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::UnbanPlayer {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        player_game_state: player_game_pda(GAME, player.id),
+        ban: player_ban_pda(GAME, player.id),
+        signer: signer.pubkey(),
+    };
+    let args = fancoin::instruction::UnbanPlayer { game_number: GAME, player_index: player.index };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+async fn appeal(env: &mut TestEnv, authority: &Keypair, player: &Player, message: &str) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::AppealBan {
+        player_pda: player_pda(player.index),
+        ban: player_ban_pda(GAME, player.id),
+        authority: authority.pubkey(),
+    };
+    let args = fancoin::instruction::AppealBan {
+        game_number: GAME,
+        player_index: player.index,
+        message: message.to_string(),
+    };
+    env.send(&[instruction(accounts, args)], &[authority]).await
+}
+
+async fn dismiss(env: &mut TestEnv, signer: &Keypair, player: &Player) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ReviewBanAppeal {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        ban: player_ban_pda(GAME, player.id),
+        signer: signer.pubkey(),
+    };
+    let args = fancoin::instruction::DismissBanAppeal { game_number: GAME, player_index: player.index };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+#[tokio::test]
+async fn banned_players_are_skipped_until_the_ban_lapses() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    let (validators, [alice, bob], server) = moderated_game(&mut env, &creator).await;
+
+    let stranger = env.funded_keypair().await;
+    assert_error(ban(&mut env, &stranger, &alice, None).await, ErrorCode::Unauthorized);
+    let now = env.now().await;
+    assert_error(ban(&mut env, &creator, &alice, Some(now)).await, ErrorCode::InvalidTimestamp);
+    ban(&mut env, &creator, &alice, Some(now + 60)).await.unwrap();
+    let state: PlayerGameState = env.account(&player_game_pda(GAME, alice.id)).await;
+    assert_eq!(state.banned_until, Some(now + 60));
+
+    // The list still goes through for bob
+    env.submit(GAME, 0, &server, &validators[0], &[&alice, &bob]).await.unwrap();
+    let open: Vec<u64> = env.game(GAME).await.minting_agreements.iter().map(|a| a.player_id).collect();
+    assert_eq!(open, [bob.id]);
+
+    env.set_time(now + 60).await;
+    env.submit(GAME, 0, &server, &validators[1], &[&alice]).await.unwrap();
+    let open: Vec<u64> = env.game(GAME).await.minting_agreements.iter().map(|a| a.player_id).collect();
+    assert!(open.contains(&alice.id));
+}
+
+#[tokio::test]
+async fn appeals_are_filed_by_the_player_and_answered_by_the_admin() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    let (_, [alice, _], _) = moderated_game(&mut env, &creator).await;
+    let owner = env.owner.insecure_clone();
+    ban(&mut env, &owner, &alice, None).await.unwrap();
+
+    let stranger = env.funded_keypair().await;
+    assert_error(appeal(&mut env, &stranger, &alice, "not me").await, ErrorCode::Unauthorized);
+    let long_message = "m".repeat(fancoin::MAX_APPEAL_MESSAGE_LEN + 1);
+    assert_error(appeal(&mut env, &alice.authority, &alice, &long_message).await, ErrorCode::AppealTooLong);
+    appeal(&mut env, &alice.authority, &alice, "not me").await.unwrap();
+    assert_error(appeal(&mut env, &alice.authority, &alice, "again").await, ErrorCode::AppealPending);
+
+    assert_error(dismiss(&mut env, &stranger, &alice).await, ErrorCode::Unauthorized);
+    dismiss(&mut env, &creator, &alice).await.unwrap();
+    assert_error(dismiss(&mut env, &creator, &alice).await, ErrorCode::NoAppealPending);
+    let ban_account: PlayerBan = env.account(&player_ban_pda(GAME, alice.id)).await;
+    assert!(ban_account.appeal.is_none());
+
+    // Granting a later appeal lifts the ban and refunds its rent
+    appeal(&mut env, &alice.authority, &alice, "please").await.unwrap();
+    assert_error(unban(&mut env, &stranger, &alice).await, ErrorCode::Unauthorized);
+    let rent = env.lamports(&player_ban_pda(GAME, alice.id)).await;
+    let creator_before = env.lamports(&creator.pubkey()).await;
+    unban(&mut env, &creator, &alice).await.unwrap();
+    assert!(!env.exists(&player_ban_pda(GAME, alice.id)).await);
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_before + rent);
+    let state: PlayerGameState = env.account(&player_game_pda(GAME, alice.id)).await;
+    assert_eq!(state.banned_until, None);
+}
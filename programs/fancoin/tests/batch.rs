@@ -0,0 +1,103 @@
+mod common;
+
+use common::*;
+use fancoin::{BatchGameResult, BatchPlayer, ErrorCode, GameBatch, GroupingStrategy, MAX_BATCH_GAMES};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    sysvar,
+};
+
+/// The ed25519 check and `submit_minting_batch` for `validator`, with one entry per
+/// (game, server, players). Each server attests its game's players for epoch 0.
+fn batch_ixs(validator: &Pubkey, entries: &[(u32, &Keypair, &[&Player])]) -> Vec<Instruction> {
+    let mut signed = Vec::new();
+    let mut games = Vec::new();
+    let mut remaining = Vec::new();
+    for (game_number, server, players) in entries {
+        remaining.push(AccountMeta::new(game_pda(*game_number), false));
+        remaining.push(AccountMeta::new_readonly(metadata_pda(*game_number), false));
+        remaining.push(AccountMeta::new(validator_pda(*game_number, validator), false));
+        let mut batch_players = Vec::new();
+        for player in *players {
+            let message = fancoin::attestation_message(*game_number, 0, &player.name, 1);
+            signed.push((server.pubkey(), server.sign_message(&message).as_ref().to_vec(), message));
+            batch_players.push(BatchPlayer { player_index: player.index, activity_score: 1 });
+            remaining.push(AccountMeta::new_readonly(player_pda(player.index), false));
+            remaining.push(AccountMeta::new(player_game_pda(*game_number, player.id), false));
+        }
+        games.push(GameBatch { game_number: *game_number, players: batch_players });
+    }
+
+    let accounts = fancoin::accounts::SubmitMintingBatch { validator: *validator, instructions: sysvar::instructions::ID };
+    let mut submit = instruction(accounts, fancoin::instruction::SubmitMintingBatch { entries: games });
+    submit.accounts.extend(remaining);
+    vec![ed25519_attestations(&signed), submit]
+}
+
+/// Games 1 and 2 with the same validator and alice and bob joined, past their seeds.
+async fn two_games(env: &mut TestEnv) -> (Keypair, [Keypair; 2], [Player; 2]) {
+    env.create_game(1).await;
+    env.create_game(2).await;
+    let validator = env.add_validator(1).await;
+    env.register_validator(2, &validator, fancoin::MIN_VALIDATOR_STAKE).await;
+    let servers = [env.add_server(1).await, env.add_server(2).await];
+    let players = [env.register_player("alice").await, env.register_player("bob").await];
+    for game_number in [1, 2] {
+        env.set_grouping_strategy(game_number, GroupingStrategy::Ring { radius: 100 }).await;
+        for player in &players {
+            env.join_game(game_number, player).await;
+        }
+    }
+    env.seed_round(0, &[(1, &[&validator]), (2, &[&validator])]).await;
+    (validator, servers, players)
+}
+
+#[tokio::test]
+async fn malformed_batches_are_rejected_whole() {
+    let mut env = TestEnv::start().await;
+    let (validator, [server, _], [alice, bob]) = two_games(&mut env).await;
+    let key = validator.pubkey();
+
+    let repeated_game = batch_ixs(&key, &[(1, &server, &[&alice]), (1, &server, &[&bob])]);
+    assert_error(env.send(&repeated_game, &[&validator]).await, ErrorCode::DuplicateBatchGame);
+
+    // The same index twice would load the player's state twice
+    let repeated_player = batch_ixs(&key, &[(1, &server, &[&alice, &alice])]);
+    assert_error(env.send(&repeated_player, &[&validator]).await, ErrorCode::DuplicatePlayerAccount);
+
+    let entries: Vec<(u32, &Keypair, &[&Player])> =
+        (0..=MAX_BATCH_GAMES as u32).map(|n| (n, &server, &[] as &[&Player])).collect();
+    let mut too_many = batch_ixs(&key, &entries);
+    too_many[1].accounts.truncate(2);
+    assert_error(env.send(&too_many, &[&validator]).await, ErrorCode::InvalidRange);
+
+    let mut short = batch_ixs(&key, &[(1, &server, &[&alice])]);
+    short[1].accounts.pop();
+    assert_error(env.send(&short, &[&validator]).await, ErrorCode::MissingBatchAccounts);
+    assert!(env.game(1).await.minting_agreements.is_empty());
+}
+
+#[tokio::test]
+async fn a_rejected_game_leaves_the_others_going_through() {
+    let mut env = TestEnv::start().await;
+    let (validator, [server_1, server_2], [alice, bob]) = two_games(&mut env).await;
+
+    // Game 2's list is signed by game 1's server
+    let ixs = batch_ixs(&validator.pubkey(), &[(1, &server_1, &[&alice, &bob]), (2, &server_1, &[&alice])]);
+    let results: Vec<BatchGameResult> = env.simulate_return_signed(&ixs, &[&validator]).await.unwrap();
+    assert_eq!(results[0].error, None);
+    assert_eq!((results[0].outcome.approvals, results[0].outcome.pending), (2, 2));
+    let invalid_attestation = ErrorCode::InvalidAttestation as u32 + anchor_lang::error::ERROR_CODE_OFFSET;
+    assert_eq!(results[1].error, Some(invalid_attestation));
+
+    env.send(&ixs, &[&validator]).await.unwrap();
+    assert_eq!(env.game(1).await.minting_agreements.len(), 2);
+    assert!(env.game(2).await.minting_agreements.is_empty());
+
+    let ixs = batch_ixs(&validator.pubkey(), &[(2, &server_2, &[&bob])]);
+    env.send(&ixs, &[&validator]).await.unwrap();
+    assert_eq!(env.game(2).await.minting_agreements[0].player_id, bob.id);
+}
@@ -0,0 +1,552 @@
+//! Shared setup for the fancoin program tests: a bank running the program natively,
+//! PDA helpers, and the steps most tests need (games, staked validators, a finalized
+//! seed, players, attested minting lists).
+
+#![allow(dead_code)]
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, InstructionData, ToAccountMetas};
+use fancoin::{AttestedPlayer, Game, GroupingStrategy, ValidatorPda};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account_info::AccountInfo,
+    clock::Clock,
+    ed25519_program,
+    entrypoint::ProgramResult,
+    hash::Hash,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    slot_hashes::SlotHashes,
+    system_instruction, system_program, sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+/// Unix time the tests start at; games created right away begin their epoch 0 here.
+pub const START: i64 = 1_700_000_000;
+
+// Anchor ties the accounts to the instruction's lifetime; the test bank keeps them
+// alive for the whole transaction, so a leaked copy is enough.
+fn process(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    fancoin::entry(program_id, accounts, data)
+}
+
+pub fn dapp_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"dapp"], &fancoin::ID).0
+}
+
+pub fn game_pda(game_number: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"game", &game_number.to_le_bytes()], &fancoin::ID).0
+}
+
+pub fn metadata_pda(game_number: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"game_metadata", &game_number.to_le_bytes()], &fancoin::ID).0
+}
+
+pub fn validator_pda(game_number: u32, validator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"validator", &game_number.to_le_bytes(), validator.as_ref()], &fancoin::ID).0
+}
+
+pub fn validator_index_pda(game_number: u32, index: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"validator_index", &game_number.to_le_bytes(), &index.to_le_bytes()],
+        &fancoin::ID,
+    )
+    .0
+}
+
+pub fn player_pda(index: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"player_pda", &index.to_le_bytes()], &fancoin::ID).0
+}
+
+pub fn player_name_pda(name: &str) -> Pubkey {
+    Pubkey::find_program_address(&[b"player_name", name.as_bytes()], &fancoin::ID).0
+}
+
+pub fn authority_index_pda(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"player_authority", authority.as_ref()], &fancoin::ID).0
+}
+
+pub fn player_game_pda(game_number: u32, player_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"player_game", &game_number.to_le_bytes(), &player_id.to_le_bytes()],
+        &fancoin::ID,
+    )
+    .0
+}
+
+pub fn player_ban_pda(game_number: u32, player_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"player_ban", &game_number.to_le_bytes(), &player_id.to_le_bytes()],
+        &fancoin::ID,
+    )
+    .0
+}
+
+pub fn proposal_pda(proposal_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"proposal", &proposal_id.to_le_bytes()], &fancoin::ID).0
+}
+
+pub fn instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: fancoin::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Fail unless `result` is the program error `code`.
+pub fn assert_error(result: Result<(), BanksClientError>, code: fancoin::ErrorCode) {
+    assert_custom_error(result, code as u32 + anchor_lang::error::ERROR_CODE_OFFSET);
+}
+
+/// Fail unless `result` is the Anchor framework error `code`, such as a failed constraint.
+pub fn assert_anchor_error(result: Result<(), BanksClientError>, code: anchor_lang::error::ErrorCode) {
+    assert_custom_error(result, code.into());
+}
+
+fn assert_custom_error(result: Result<(), BanksClientError>, expected: u32) {
+    match result {
+        Err(err) => match err.unwrap() {
+            TransactionError::InstructionError(_, InstructionError::Custom(code)) => {
+                assert_eq!(code, expected, "wrong error code")
+            }
+            other => panic!("expected custom error {}, got {:?}", expected, other),
+        },
+        Ok(()) => panic!("expected custom error {}, but the transaction succeeded", expected),
+    }
+}
+
+pub struct Player {
+    pub index: u32,
+    pub id: u64,
+    pub name: String,
+    pub authority: Keypair,
+}
+
+pub struct TestEnv {
+    pub ctx: ProgramTestContext,
+    /// Sole DApp owner, and the payer of every transaction
+    pub owner: Keypair,
+}
+
+impl TestEnv {
+    /// A fresh bank at `START` with the DApp initialized.
+    pub async fn start() -> TestEnv {
+        let mut test = ProgramTest::new("fancoin", fancoin::ID, processor!(process));
+        test.prefer_bpf(false);
+        let ctx = test.start_with_context().await;
+        let owner = ctx.payer.insecure_clone();
+        let mut env = TestEnv { ctx, owner };
+        env.set_time(START).await;
+
+        let accounts = fancoin::accounts::InitializeDapp {
+            dapp: dapp_pda(),
+            user: env.owner.pubkey(),
+            system_program: system_program::ID,
+        };
+        env.send(&[instruction(accounts, fancoin::instruction::InitializeDapp {})], &[])
+            .await
+            .unwrap();
+        env
+    }
+
+    /// Sign with the owner and `signers`, and send.
+    pub async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.owner];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&self.owner.pubkey()), &all_signers, blockhash);
+        self.ctx.banks_client.process_transaction(tx).await
+    }
+
+    /// Simulate `instructions` and decode the program's return data as `T`.
+    pub async fn simulate_return<T: AnchorDeserialize>(
+        &mut self,
+        instructions: &[Instruction],
+    ) -> Result<T, TransactionError> {
+        self.simulate_return_signed(instructions, &[]).await
+    }
+
+    /// `simulate_return` for instructions that need `signers` besides the owner.
+    pub async fn simulate_return_signed<T: AnchorDeserialize>(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<T, TransactionError> {
+        let blockhash = self.ctx.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.owner];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&self.owner.pubkey()), &all_signers, blockhash);
+        let simulation = self.ctx.banks_client.simulate_transaction(tx).await.unwrap();
+        simulation.result.unwrap()?;
+        let return_data = simulation.simulation_details.unwrap().return_data.expect("return data");
+        Ok(T::deserialize(&mut return_data.data.as_slice()).unwrap())
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.ctx.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+    }
+
+    pub async fn set_time(&mut self, unix_timestamp: i64) {
+        let mut clock: Clock = self.ctx.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        self.ctx.set_sysvar(&clock);
+    }
+
+    pub async fn slot(&mut self) -> u64 {
+        self.ctx.banks_client.get_sysvar::<Clock>().await.unwrap().slot
+    }
+
+    /// Pretend the cluster has produced `slots` (newest first).
+    pub fn set_slot_hashes(&mut self, slots: &[u64]) {
+        let entries: Vec<(u64, Hash)> = slots.iter().map(|slot| (*slot, Hash::new_unique())).collect();
+        self.ctx.set_sysvar(&SlotHashes::new(&entries));
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, key: &Pubkey) -> T {
+        let account = self.ctx.banks_client.get_account(*key).await.unwrap().expect("account exists");
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn exists(&mut self, key: &Pubkey) -> bool {
+        self.ctx.banks_client.get_account(*key).await.unwrap().is_some()
+    }
+
+    pub async fn lamports(&mut self, key: &Pubkey) -> u64 {
+        self.ctx.banks_client.get_balance(*key).await.unwrap()
+    }
+
+    pub async fn game(&mut self, game_number: u32) -> Game {
+        self.account(&game_pda(game_number)).await
+    }
+
+    pub async fn validator(&mut self, game_number: u32, validator: &Pubkey) -> ValidatorPda {
+        self.account(&validator_pda(game_number, validator)).await
+    }
+
+    /// A new keypair holding 10 SOL.
+    pub async fn funded_keypair(&mut self) -> Keypair {
+        let keypair = Keypair::new();
+        let transfer = system_instruction::transfer(&self.owner.pubkey(), &keypair.pubkey(), 10 * LAMPORTS_PER_SOL);
+        self.send(&[transfer], &[]).await.unwrap();
+        keypair
+    }
+
+    /// Game `game_number`, created by the owner, with its epoch 0 starting now.
+    pub async fn create_game(&mut self, game_number: u32) {
+        let creator = self.owner.pubkey();
+        self.create_game_for(game_number, &creator).await;
+    }
+
+    /// Game `game_number`, created by the owner on behalf of `creator`.
+    pub async fn create_game_for(&mut self, game_number: u32, creator: &Pubkey) {
+        let accounts = fancoin::accounts::InitializeGame {
+            game: game_pda(game_number),
+            dapp: dapp_pda(),
+            user: self.owner.pubkey(),
+            system_program: system_program::ID,
+        };
+        let args = fancoin::instruction::InitializeGame {
+            game_number,
+            description: format!("game {}", game_number),
+            creator: *creator,
+        };
+        self.send(&[instruction(accounts, args)], &[]).await.unwrap();
+    }
+
+    pub fn update_game(&self, game_number: u32) -> fancoin::accounts::UpdateGameStatus {
+        fancoin::accounts::UpdateGameStatus {
+            game: game_pda(game_number),
+            dapp: dapp_pda(),
+            signer: self.owner.pubkey(),
+        }
+    }
+
+    pub async fn set_grouping_strategy(&mut self, game_number: u32, grouping_strategy: GroupingStrategy) {
+        let accounts = self.update_game(game_number);
+        let args = fancoin::instruction::SetGroupingStrategy { game_number, grouping_strategy };
+        self.send(&[instruction(accounts, args)], &[]).await.unwrap();
+    }
+
+    /// Register `validator` for the game with `stake` lamports staked.
+    pub async fn register_validator(&mut self, game_number: u32, validator: &Keypair, stake: u64) {
+        let index = self.game(game_number).await.validator_count;
+        let register = fancoin::accounts::RegisterValidatorPda {
+            game: game_pda(game_number),
+            validator_pda: validator_pda(game_number, &validator.pubkey()),
+            validator_index: validator_index_pda(game_number, index),
+            user: validator.pubkey(),
+            system_program: system_program::ID,
+        };
+        let mut instructions =
+            vec![instruction(register, fancoin::instruction::RegisterValidatorPda { game_number })];
+        if stake > 0 {
+            let accounts = fancoin::accounts::StakeValidator {
+                validator_pda: validator_pda(game_number, &validator.pubkey()),
+                game: game_pda(game_number),
+                user: validator.pubkey(),
+                system_program: system_program::ID,
+            };
+            instructions.push(instruction(accounts, fancoin::instruction::StakeValidator { amount: stake }));
+        }
+        self.send(&instructions, &[validator]).await.unwrap();
+    }
+
+    /// A new validator for the game holding the minimum stake.
+    pub async fn add_validator(&mut self, game_number: u32) -> Keypair {
+        let validator = self.funded_keypair().await;
+        self.register_validator(game_number, &validator, fancoin::MIN_VALIDATOR_STAKE).await;
+        validator
+    }
+
+    /// Start time of `epoch` in the game.
+    pub async fn epoch_start(&mut self, game_number: u32, epoch: u64) -> i64 {
+        let game = self.game(game_number).await;
+        game.epoch_start + (epoch - game.epoch_base) as i64 * game.epoch_length
+    }
+
+    pub fn punch_in_ix(game_number: u32, validator: &Pubkey, epoch: u64) -> Instruction {
+        let accounts = fancoin::accounts::PunchIn {
+            game: game_pda(game_number),
+            validator_pda: validator_pda(game_number, validator),
+            validator: *validator,
+            system_program: system_program::ID,
+        };
+        let commitment = fancoin::seed_commitment(&secret(validator, epoch), validator);
+        instruction(accounts, fancoin::instruction::PunchIn { game_number, commitment })
+    }
+
+    pub fn reveal_ix(game_number: u32, validator: &Pubkey, epoch: u64) -> Instruction {
+        let accounts = fancoin::accounts::RevealSeed {
+            game: game_pda(game_number),
+            validator_pda: validator_pda(game_number, validator),
+            validator: *validator,
+        };
+        instruction(accounts, fancoin::instruction::RevealSeed { game_number, secret: secret(validator, epoch) })
+    }
+
+    pub fn finalize_ix(game_number: u32) -> Instruction {
+        let accounts = fancoin::accounts::FinalizeSeed { game: game_pda(game_number), slot_hashes: sysvar::slot_hashes::ID };
+        instruction(accounts, fancoin::instruction::FinalizeSeed { game_number })
+    }
+
+    /// Run the commit-reveal round of `epoch` in every game of `games`, with each
+    /// game's `validators` committing and revealing, and finalize the seeds. Leaves the
+    /// clock at the start of the mint window.
+    pub async fn seed_round(&mut self, epoch: u64, games: &[(u32, &[&Keypair])]) {
+        let (first_game, _) = games[0];
+        let start = self.epoch_start(first_game, epoch).await;
+        let game = self.game(first_game).await;
+
+        self.set_time(start + 1).await;
+        for (game_number, validators) in games {
+            for validator in *validators {
+                let ix = Self::punch_in_ix(*game_number, &validator.pubkey(), epoch);
+                self.send(&[ix], &[validator]).await.unwrap();
+            }
+        }
+
+        self.set_time(start + game.punch_in_length).await;
+        for (game_number, validators) in games {
+            for validator in *validators {
+                let ix = Self::reveal_ix(*game_number, &validator.pubkey(), epoch);
+                self.send(&[ix], &[validator]).await.unwrap();
+            }
+        }
+
+        self.set_time(start + game.punch_in_length + game.reveal_length).await;
+        for (game_number, _) in games {
+            self.finalize_seed(*game_number).await;
+        }
+    }
+
+    /// Finalize the game's seed: fix the seed slot, then pretend it was produced.
+    pub async fn finalize_seed(&mut self, game_number: u32) {
+        self.send(&[Self::finalize_ix(game_number)], &[]).await.unwrap();
+        let seed_slot = self.game(game_number).await.seed_slot;
+        self.set_slot_hashes(&[seed_slot]);
+        self.send(&[Self::finalize_ix(game_number)], &[]).await.unwrap();
+    }
+
+    /// Register a player under a fresh authority.
+    pub async fn register_player(&mut self, name: &str) -> Player {
+        let authority = Keypair::new();
+        let dapp: fancoin::DApp = self.account(&dapp_pda()).await;
+        self.try_register_player(name, &authority).await.unwrap();
+        Player {
+            index: dapp.global_player_count,
+            id: dapp.next_player_id,
+            name: name.to_string(),
+            authority,
+        }
+    }
+
+    /// Register a player named `name` controlled by `authority`, paid to the authority.
+    pub async fn try_register_player(&mut self, name: &str, authority: &Keypair) -> Result<(), BanksClientError> {
+        let dapp: fancoin::DApp = self.account(&dapp_pda()).await;
+        let accounts = fancoin::accounts::RegisterPlayerPda {
+            dapp: dapp_pda(),
+            player_pda: player_pda(dapp.global_player_count),
+            authority_index: authority_index_pda(&authority.pubkey()),
+            player_name: player_name_pda(name),
+            authority: authority.pubkey(),
+            user: self.owner.pubkey(),
+            system_program: system_program::ID,
+        };
+        let args = fancoin::instruction::RegisterPlayerPda {
+            name: name.to_string(),
+            reward_address: authority.pubkey(),
+        };
+        self.send(&[instruction(accounts, args)], &[authority]).await
+    }
+
+    /// Create the player's `PlayerGameState` in the game.
+    pub async fn join_game(&mut self, game_number: u32, player: &Player) {
+        let accounts = fancoin::accounts::InitPlayerGameState {
+            game: game_pda(game_number),
+            player_pda: player_pda(player.index),
+            player_game_state: player_game_pda(game_number, player.id),
+            user: self.owner.pubkey(),
+            system_program: system_program::ID,
+        };
+        let args = fancoin::instruction::InitPlayerGameState { game_number, player_index: player.index };
+        self.send(&[instruction(accounts, args)], &[]).await.unwrap();
+    }
+
+    /// Give the game metadata and a registered server; returns the server's signing key.
+    pub async fn add_server(&mut self, game_number: u32) -> Keypair {
+        let server = Keypair::new();
+        let metadata = fancoin::accounts::SetGameMetadata {
+            game: game_pda(game_number),
+            dapp: dapp_pda(),
+            metadata: metadata_pda(game_number),
+            signer: self.owner.pubkey(),
+            system_program: system_program::ID,
+        };
+        let set_metadata = fancoin::instruction::SetGameMetadata {
+            game_number,
+            name: format!("game {}", game_number),
+            uri: String::new(),
+        };
+        let servers = fancoin::accounts::UpdateGameServers {
+            game: game_pda(game_number),
+            dapp: dapp_pda(),
+            metadata: metadata_pda(game_number),
+            signer: self.owner.pubkey(),
+        };
+        let add_server = fancoin::instruction::AddGameServer {
+            game_number,
+            endpoint: "https://example.invalid".to_string(),
+            signing_key: server.pubkey(),
+        };
+        self.send(&[instruction(metadata, set_metadata), instruction(servers, add_server)], &[])
+            .await
+            .unwrap();
+        server
+    }
+
+    /// Instructions for `validator` to submit `players`, attested by `server` for `epoch`.
+    pub fn submit_ixs(
+        game_number: u32,
+        epoch: u64,
+        server: &Keypair,
+        validator: &Pubkey,
+        players: &[&Player],
+    ) -> Vec<Instruction> {
+        let attested: Vec<AttestedPlayer> = players
+            .iter()
+            .map(|player| AttestedPlayer { player_name: player.name.clone(), activity_score: 1 })
+            .collect();
+        let signed: Vec<(Pubkey, Vec<u8>, Vec<u8>)> = attested
+            .iter()
+            .map(|player| {
+                let message =
+                    fancoin::attestation_message(game_number, epoch, &player.player_name, player.activity_score);
+                let signature = server.sign_message(&message);
+                (server.pubkey(), signature.as_ref().to_vec(), message)
+            })
+            .collect();
+
+        let accounts = fancoin::accounts::SubmitMintingList {
+            game: game_pda(game_number),
+            validator: *validator,
+            metadata: metadata_pda(game_number),
+            instructions: sysvar::instructions::ID,
+        };
+        let mut submit =
+            instruction(accounts, fancoin::instruction::SubmitMintingList { game_number, players: attested });
+        submit.accounts.push(AccountMeta::new(validator_pda(game_number, validator), false));
+        for player in players {
+            submit.accounts.push(AccountMeta::new_readonly(player_pda(player.index), false));
+            submit.accounts.push(AccountMeta::new(player_game_pda(game_number, player.id), false));
+        }
+        vec![ed25519_attestations(&signed), submit]
+    }
+
+    pub async fn submit(
+        &mut self,
+        game_number: u32,
+        epoch: u64,
+        server: &Keypair,
+        validator: &Keypair,
+        players: &[&Player],
+    ) -> Result<(), BanksClientError> {
+        let ixs = Self::submit_ixs(game_number, epoch, server, &validator.pubkey(), players);
+        self.send(&ixs, &[validator]).await
+    }
+}
+
+/// The secret `validator` commits to for `epoch`.
+pub fn secret(validator: &Pubkey, epoch: u64) -> [u8; 32] {
+    let mut secret = validator.to_bytes();
+    for (byte, e) in secret.iter_mut().zip(epoch.to_le_bytes()) {
+        *byte ^= e;
+    }
+    secret
+}
+
+/// Whether `validator` is in the group of `player` under the game's current seed.
+pub fn in_player_group(game: &Game, validator: &Pubkey, player: &Player) -> bool {
+    let strategy = game.grouping_strategy;
+    let distance = strategy
+        .player_distance(validator, player.id, game.last_seed.unwrap(), game.validator_count)
+        .unwrap();
+    distance <= strategy.tolerance(game.validator_count)
+}
+
+/// One ed25519 precompile instruction checking every (key, signature, message), with all
+/// offsets pointing into the instruction itself.
+pub fn ed25519_attestations(signed: &[(Pubkey, Vec<u8>, Vec<u8>)]) -> Instruction {
+    const HEADER_LEN: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const THIS_INSTRUCTION: u16 = u16::MAX;
+
+    let mut data = vec![signed.len() as u8, 0];
+    let mut payload = Vec::new();
+    let payload_start = HEADER_LEN + signed.len() * OFFSETS_LEN;
+    for (key, signature, message) in signed {
+        let key_offset = payload_start + payload.len();
+        payload.extend_from_slice(key.as_ref());
+        let signature_offset = payload_start + payload.len();
+        payload.extend_from_slice(signature);
+        let message_offset = payload_start + payload.len();
+        payload.extend_from_slice(message);
+
+        for value in [
+            signature_offset as u16,
+            THIS_INSTRUCTION,
+            key_offset as u16,
+            THIS_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            THIS_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    data.extend(payload);
+    Instruction::new_with_bytes(ed25519_program::ID, &data, vec![])
+}
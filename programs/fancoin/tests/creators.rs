@@ -0,0 +1,60 @@
+mod common;
+
+use common::*;
+use fancoin::ErrorCode;
+use solana_program_test::BanksClientError;
+use solana_sdk::{signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+async fn set_description(env: &mut TestEnv, signer: &Keypair, description: String) -> Result<(), BanksClientError> {
+    let mut accounts = env.update_game(GAME);
+    accounts.signer = signer.pubkey();
+    let args = fancoin::instruction::SetGameDescription { game_number: GAME, description };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+#[tokio::test]
+async fn only_owners_create_games() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+
+    let accounts = fancoin::accounts::InitializeGame {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        user: creator.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::InitializeGame {
+        game_number: GAME,
+        description: "mine".to_string(),
+        creator: creator.pubkey(),
+    };
+    assert_error(env.send(&[instruction(accounts, args)], &[&creator]).await, ErrorCode::Unauthorized);
+
+    env.create_game_for(GAME, &creator.pubkey()).await;
+    assert_eq!(env.game(GAME).await.creator, creator.pubkey());
+}
+
+#[tokio::test]
+async fn creators_manage_their_games_description() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    env.create_game_for(GAME, &creator.pubkey()).await;
+
+    set_description(&mut env, &creator, "a better game".to_string()).await.unwrap();
+    assert_eq!(env.game(GAME).await.description, "a better game");
+
+    let too_long = "x".repeat(fancoin::MAX_GAME_DESCRIPTION_LEN + 1);
+    assert_error(set_description(&mut env, &creator, too_long).await, ErrorCode::DescriptionTooLong);
+
+    let stranger = env.funded_keypair().await;
+    assert_error(
+        set_description(&mut env, &stranger, "not mine".to_string()).await,
+        ErrorCode::Unauthorized,
+    );
+
+    // Owners keep control over every game
+    let owner = env.owner.insecure_clone();
+    set_description(&mut env, &owner, "curated".to_string()).await.unwrap();
+}
@@ -0,0 +1,169 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, PlayerGameState, PlayerPda};
+use solana_program_test::BanksClientError;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+/// `init_player_game_state` paid by `payer` instead of the owner.
+async fn join_paid_by(env: &mut TestEnv, payer: &Keypair, player: &Player) {
+    let accounts = fancoin::accounts::InitPlayerGameState {
+        game: game_pda(GAME),
+        player_pda: player_pda(player.index),
+        player_game_state: player_game_pda(GAME, player.id),
+        user: payer.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::InitPlayerGameState { game_number: GAME, player_index: player.index };
+    env.send(&[instruction(accounts, args)], &[payer]).await.unwrap();
+}
+
+/// `close_player_game_state`, passing the player's ban and `ban_refund` if given one.
+async fn leave(
+    env: &mut TestEnv,
+    authority: &Keypair,
+    player: &Player,
+    payer: &Pubkey,
+    ban_refund: Option<Pubkey>,
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ClosePlayerGameState {
+        game: game_pda(GAME),
+        player_pda: player_pda(player.index),
+        player_game_state: player_game_pda(GAME, player.id),
+        ban: ban_refund.map(|_| player_ban_pda(GAME, player.id)),
+        ban_refund,
+        payer: *payer,
+        authority: authority.pubkey(),
+    };
+    let args = fancoin::instruction::ClosePlayerGameState { game_number: GAME, player_index: player.index };
+    env.send(&[instruction(accounts, args)], &[authority]).await
+}
+
+async fn ban(env: &mut TestEnv, signer: &Keypair, player: &Player, expires_at: Option<i64>) {
+    let accounts = fancoin::accounts::BanPlayer {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        player_game_state: player_game_pda(GAME, player.id),
+        ban: player_ban_pda(GAME, player.id),
+        signer: signer.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::BanPlayer { game_number: GAME, player_index: player.index, reason_code: 7, expires_at };
+    env.send(&[instruction(accounts, args)], &[signer]).await.unwrap();
+}
+
+/// Deregister `player`, the last registered player.
+async fn deregister(env: &mut TestEnv, player: &Player) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::DeregisterPlayer {
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        player_name: player_name_pda(&player.name),
+        authority_index: authority_index_pda(&player.authority.pubkey()),
+        last_player_pda: None,
+        moved_authority_index: None,
+        authority: player.authority.pubkey(),
+    };
+    let args = fancoin::instruction::DeregisterPlayer { player_index: player.index };
+    env.send(&[instruction(accounts, args)], &[&player.authority]).await
+}
+
+#[tokio::test]
+async fn players_close_their_game_states_before_deregistering() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validator = env.add_validator(GAME).await;
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    let payer = env.funded_keypair().await;
+    join_paid_by(&mut env, &payer, &alice).await;
+    let player: PlayerPda = env.account(&player_pda(alice.index)).await;
+    assert_eq!(player.game_state_count, 1);
+    env.seed_round(0, &[(GAME, &[&validator])]).await;
+    env.submit(GAME, 0, &server, &validator, &[&alice]).await.unwrap();
+    assert_eq!(env.game(GAME).await.minting_agreements.len(), 1);
+
+    assert_error(deregister(&mut env, &alice).await, ErrorCode::PlayerHasGameStates);
+    let stranger = env.funded_keypair().await;
+    assert_error(leave(&mut env, &stranger, &alice, &payer.pubkey(), None).await, ErrorCode::Unauthorized);
+
+    // The rent goes back to whoever paid for the state, and the open agreement goes with it
+    let rent = env.lamports(&player_game_pda(GAME, alice.id)).await;
+    let payer_before = env.lamports(&payer.pubkey()).await;
+    leave(&mut env, &alice.authority, &alice, &payer.pubkey(), None).await.unwrap();
+    assert!(!env.exists(&player_game_pda(GAME, alice.id)).await);
+    assert_eq!(env.lamports(&payer.pubkey()).await, payer_before + rent);
+    assert!(env.game(GAME).await.minting_agreements.is_empty());
+
+    deregister(&mut env, &alice).await.unwrap();
+    assert!(!env.exists(&player_pda(alice.index)).await);
+}
+
+#[tokio::test]
+async fn payouts_and_bans_keep_the_state_open() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let [alice, bob] = [env.register_player("alice").await, env.register_player("bob").await];
+    env.join_game(GAME, &alice).await;
+    env.join_game(GAME, &bob).await;
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+    let owner = env.owner.pubkey();
+    assert_error(leave(&mut env, &alice.authority, &alice, &owner, None).await, ErrorCode::PlayerHasPendingPayout);
+
+    let moderator = env.funded_keypair().await;
+    let admin = env.owner.insecure_clone();
+    let now = env.now().await;
+    ban(&mut env, &admin, &bob, Some(now + 60)).await;
+    let ban_account = player_ban_pda(GAME, bob.id);
+    assert_error(leave(&mut env, &bob.authority, &bob, &owner, Some(owner)).await, ErrorCode::PlayerBanned);
+
+    // A lapsed ban is closed along with the state, its rent going to whoever banned
+    env.set_time(now + 60).await;
+    assert_error(leave(&mut env, &bob.authority, &bob, &owner, None).await, ErrorCode::MissingBanAccounts);
+    let wrong_refund = Some(moderator.pubkey());
+    assert_error(leave(&mut env, &bob.authority, &bob, &owner, wrong_refund).await, ErrorCode::MissingBanAccounts);
+    leave(&mut env, &bob.authority, &bob, &owner, Some(owner)).await.unwrap();
+    assert!(!env.exists(&ban_account).await);
+    assert!(!env.exists(&player_game_pda(GAME, bob.id)).await);
+}
+
+#[tokio::test]
+async fn states_in_a_closed_game_can_always_be_closed() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    env.create_game_for(GAME, &creator.pubkey()).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+    ban(&mut env, &creator, &alice, None).await;
+
+    let accounts = env.update_game(GAME);
+    let args = fancoin::instruction::RetireGame { game_number: GAME, reason: "sunset".to_string() };
+    env.send(&[instruction(accounts, args)], &[]).await.unwrap();
+    let accounts = fancoin::accounts::CloseGame {
+        game: game_pda(GAME),
+        metadata: metadata_pda(GAME),
+        creator: creator.pubkey(),
+        dapp: dapp_pda(),
+        signer: creator.pubkey(),
+    };
+    env.send(&[instruction(accounts, fancoin::instruction::CloseGame { game_number: GAME })], &[&creator])
+        .await
+        .unwrap();
+
+    // Even a permanent ban no longer holds the state once its game is gone
+    let state: PlayerGameState = env.account(&player_game_pda(GAME, alice.id)).await;
+    let ban_rent = env.lamports(&player_ban_pda(GAME, alice.id)).await;
+    let creator_before = env.lamports(&creator.pubkey()).await;
+    leave(&mut env, &alice.authority, &alice, &state.payer, Some(creator.pubkey())).await.unwrap();
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_before + ban_rent);
+    deregister(&mut env, &alice).await.unwrap();
+}
@@ -0,0 +1,165 @@
+mod common;
+
+use common::*;
+use fancoin::{DApp, DisputeRuling, ErrorCode, GroupingStrategy, PlayerGameState, ProposalAction};
+use solana_program_test::BanksClientError;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+/// Two validators in every player's group, with a pending payout for alice and bob each.
+async fn disputable_game(env: &mut TestEnv) -> ([Keypair; 2], [Player; 2]) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let players = [env.register_player("alice").await, env.register_player("bob").await];
+    for player in &players {
+        env.join_game(GAME, player).await;
+    }
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&players[0], &players[1]]).await.unwrap();
+    }
+    assert_eq!(env.game(GAME).await.pending_payouts.len(), 2);
+    (validators, players)
+}
+
+async fn dispute(
+    env: &mut TestEnv,
+    validator: &Keypair,
+    player_id: u64,
+    epoch: u64,
+    evidence: &str,
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::DisputePayout {
+        game: game_pda(GAME),
+        validator_pda: validator_pda(GAME, &validator.pubkey()),
+        validator: validator.pubkey(),
+    };
+    let args = fancoin::instruction::DisputePayout {
+        game_number: GAME,
+        player_id,
+        epoch,
+        evidence: evidence.to_string(),
+    };
+    env.send(&[instruction(accounts, args)], &[validator]).await
+}
+
+async fn resolve(
+    env: &mut TestEnv,
+    signer: &Keypair,
+    player_id: u64,
+    state: Option<Pubkey>,
+    ruling: DisputeRuling,
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ResolveDispute {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        player_game_state: state,
+        signer: signer.pubkey(),
+    };
+    let args = fancoin::instruction::ResolveDispute { game_number: GAME, player_id, epoch: 0, ruling };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+#[tokio::test]
+async fn disputes_are_keyed_by_player_id() {
+    let mut env = TestEnv::start().await;
+    let (validators, [alice, bob]) = disputable_game(&mut env).await;
+    let validator = &validators[0];
+
+    assert_error(dispute(&mut env, validator, bob.id, 1, "").await, ErrorCode::PayoutNotFound);
+    assert_error(dispute(&mut env, validator, u64::MAX, 0, "").await, ErrorCode::PayoutNotFound);
+    let long_evidence = "e".repeat(fancoin::MAX_DISPUTE_EVIDENCE_LEN + 1);
+    assert_error(dispute(&mut env, validator, bob.id, 0, &long_evidence).await, ErrorCode::EvidenceTooLong);
+
+    dispute(&mut env, validator, bob.id, 0, "ipfs://evidence").await.unwrap();
+    assert_error(
+        dispute(&mut env, &validators[1], bob.id, 0, "").await,
+        ErrorCode::PayoutAlreadyDisputed,
+    );
+    let game = env.game(GAME).await;
+    let disputed: Vec<u64> =
+        game.pending_payouts.iter().filter(|p| p.dispute.is_some()).map(|p| p.player_id).collect();
+    assert_eq!(disputed, [bob.id]);
+
+    // The challenge window has closed on alice's payout
+    let release_at = game.pending_payouts[0].release_at;
+    env.set_time(release_at).await;
+    assert_error(dispute(&mut env, validator, alice.id, 0, "").await, ErrorCode::ChallengeWindowClosed);
+}
+
+#[tokio::test]
+async fn owners_rule_on_disputed_payouts() {
+    let mut env = TestEnv::start().await;
+    let (validators, [alice, bob]) = disputable_game(&mut env).await;
+    let owner = env.owner.insecure_clone();
+    let bob_state = player_game_pda(GAME, bob.id);
+    let minted_before: PlayerGameState = env.account(&bob_state).await;
+
+    assert_error(
+        resolve(&mut env, &owner, alice.id, None, DisputeRuling::Pay).await,
+        ErrorCode::PayoutNotDisputed,
+    );
+    dispute(&mut env, &validators[0], bob.id, 0, "").await.unwrap();
+    let stranger = env.funded_keypair().await;
+    assert_error(
+        resolve(&mut env, &stranger, bob.id, None, DisputeRuling::Reject).await,
+        ErrorCode::Unauthorized,
+    );
+    assert_error(
+        resolve(&mut env, &owner, bob.id, None, DisputeRuling::Reject).await,
+        ErrorCode::MissingPlayerGameState,
+    );
+    let alice_state = Some(player_game_pda(GAME, alice.id));
+    assert_error(
+        resolve(&mut env, &owner, bob.id, alice_state, DisputeRuling::Reject).await,
+        ErrorCode::InvalidSeeds,
+    );
+
+    resolve(&mut env, &owner, bob.id, Some(bob_state), DisputeRuling::Reject).await.unwrap();
+    let game = env.game(GAME).await;
+    assert_eq!(game.pending_payouts.len(), 1);
+    assert_eq!(game.pending_payouts[0].player_id, alice.id);
+    let state: PlayerGameState = env.account(&bob_state).await;
+    assert_eq!(state.total_minted, 0);
+    assert!(minted_before.total_minted > 0);
+}
+
+#[tokio::test]
+async fn disputes_can_be_ruled_on_by_proposal() {
+    let mut env = TestEnv::start().await;
+    let (validators, [alice, _]) = disputable_game(&mut env).await;
+    let owner = env.owner.insecure_clone();
+    dispute(&mut env, &validators[1], alice.id, 0, "").await.unwrap();
+
+    let dapp: DApp = env.account(&dapp_pda()).await;
+    let proposal_id = dapp.proposal_count;
+    let action =
+        ProposalAction::ResolveDispute { game_number: GAME, player_id: alice.id, epoch: 0, ruling: DisputeRuling::Pay };
+    let accounts = fancoin::accounts::ProposeAction {
+        dapp: dapp_pda(),
+        proposal: proposal_pda(proposal_id),
+        proposer: owner.pubkey(),
+        system_program: system_program::ID,
+    };
+    env.send(&[instruction(accounts, fancoin::instruction::ProposeAction { action })], &[]).await.unwrap();
+
+    let accounts = fancoin::accounts::ExecuteProposal {
+        dapp: dapp_pda(),
+        proposal: proposal_pda(proposal_id),
+        game: Some(game_pda(GAME)),
+        new_game: None,
+        player_game_state: None,
+        proposer: owner.pubkey(),
+        executor: owner.pubkey(),
+        system_program: system_program::ID,
+    };
+    env.send(&[instruction(accounts, fancoin::instruction::ExecuteProposal { proposal_id })], &[]).await.unwrap();
+
+    // Paid right away, leaving only bob's payout queued
+    let game = env.game(GAME).await;
+    assert_eq!(game.pending_payouts.len(), 1);
+    assert_ne!(game.pending_payouts[0].player_id, alice.id);
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy};
+use solana_sdk::signer::Signer;
+
+const GAME: u32 = 1;
+
+#[tokio::test]
+async fn punch_in_only_during_the_punch_in_window() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validator = env.add_validator(GAME).await;
+    let punch_in_length = env.game(GAME).await.punch_in_length;
+
+    env.set_time(START + punch_in_length).await;
+    let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), 0);
+    assert_error(env.send(&[ix], &[&validator]).await, ErrorCode::NotInPunchInPeriod);
+
+    env.set_time(START + punch_in_length - 1).await;
+    let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), 0);
+    env.send(&[ix], &[&validator]).await.unwrap();
+    assert_eq!(env.game(GAME).await.seed_epoch, Some(0));
+}
+
+#[tokio::test]
+async fn minting_lists_only_during_the_mint_window() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let first = env.add_validator(GAME).await;
+    let second = env.add_validator(GAME).await;
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+
+    env.set_time(START + 1).await;
+    assert_error(env.submit(GAME, 0, &server, &first, &[&alice]).await, ErrorCode::NotInMintPeriod);
+
+    env.seed_round(0, &[(GAME, &[&first, &second])]).await;
+    env.submit(GAME, 0, &server, &first, &[&alice]).await.unwrap();
+    env.submit(GAME, 0, &server, &second, &[&alice]).await.unwrap();
+
+    let game = env.game(GAME).await;
+    assert!(game.minting_agreements.is_empty());
+    assert_eq!(game.pending_payouts.len(), 1);
+    assert_eq!(game.pending_payouts[0].player_id, alice.id);
+}
+
+#[tokio::test]
+async fn configuring_epochs_starts_a_fresh_epoch() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validator = env.add_validator(GAME).await;
+    env.seed_round(0, &[(GAME, &[&validator])]).await;
+    assert!(env.game(GAME).await.last_seed.is_some());
+
+    let accounts = env.update_game(GAME);
+    let bad = fancoin::instruction::ConfigureGameEpochs {
+        game_number: GAME,
+        epoch_length: 100,
+        punch_in_length: 60,
+        reveal_length: 40,
+    };
+    assert_error(env.send(&[instruction(accounts, bad)], &[]).await, ErrorCode::InvalidEpochConfig);
+
+    let now = env.now().await;
+    let accounts = env.update_game(GAME);
+    let good = fancoin::instruction::ConfigureGameEpochs {
+        game_number: GAME,
+        epoch_length: 100,
+        punch_in_length: 30,
+        reveal_length: 20,
+    };
+    env.send(&[instruction(accounts, good)], &[]).await.unwrap();
+
+    let game = env.game(GAME).await;
+    assert_eq!((game.epoch_base, game.epoch_start), (1, now));
+    assert_eq!((game.last_seed, game.seed_epoch), (None, None));
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, ValidatorIndex, DEFAULT_INACTIVITY_SECONDS, MIN_VALIDATOR_STAKE};
+use solana_program_test::BanksClientError;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+const GAME: u32 = 1;
+
+async fn evict(env: &mut TestEnv, validator: &Pubkey, moved: Option<&Pubkey>) -> Result<(), BanksClientError> {
+    let val_pda = env.validator(GAME, validator).await;
+    let last = env.game(GAME).await.validator_count - 1;
+    let accounts = fancoin::accounts::EvictInactiveValidator {
+        game: game_pda(GAME),
+        validator_pda: validator_pda(GAME, validator),
+        validator_index: validator_index_pda(GAME, val_pda.index),
+        last_validator_index: moved.map(|_| validator_index_pda(GAME, last)),
+        moved_validator_pda: moved.map(|moved| validator_pda(GAME, moved)),
+        validator_wallet: *validator,
+        cranker: env.owner.pubkey(),
+    };
+    let ix = instruction(accounts, fancoin::instruction::EvictInactiveValidator { game_number: GAME });
+    env.send(&[ix], &[]).await
+}
+
+#[tokio::test]
+async fn punching_in_keeps_a_validator_active() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validator = env.add_validator(GAME).await;
+
+    // Punch in just before the registration would go stale
+    let idle_at = START + DEFAULT_INACTIVITY_SECONDS;
+    let epoch = env.game(GAME).await.epoch_at(idle_at - 1).unwrap().0;
+    let punch_in_at = env.epoch_start(GAME, epoch).await + 1;
+    assert!(punch_in_at <= idle_at);
+    env.set_time(punch_in_at).await;
+    let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), epoch);
+    env.send(&[ix], &[&validator]).await.unwrap();
+    assert_eq!(env.validator(GAME, &validator.pubkey()).await.last_activity, punch_in_at);
+
+    env.set_time(idle_at + 1).await;
+    assert_error(evict(&mut env, &validator.pubkey(), None).await, ErrorCode::ValidatorStillActive);
+}
+
+#[tokio::test]
+async fn idle_validators_are_evicted_keeping_indexes_dense() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validators: Vec<Keypair> = vec![
+        env.add_validator(GAME).await,
+        env.add_validator(GAME).await,
+        env.add_validator(GAME).await,
+    ];
+    let (idle, last) = (validators[0].pubkey(), validators[2].pubkey());
+
+    assert_error(evict(&mut env, &idle, Some(&last)).await, ErrorCode::ValidatorStillActive);
+
+    env.set_time(START + DEFAULT_INACTIVITY_SECONDS + 1).await;
+    // Not the last index, so the last entry must be moved into its slot
+    assert_error(evict(&mut env, &idle, None).await, ErrorCode::MissingValidatorIndex);
+
+    let wallet_before = env.lamports(&idle).await;
+    evict(&mut env, &idle, Some(&last)).await.unwrap();
+
+    assert!(!env.exists(&validator_pda(GAME, &idle)).await);
+    assert!(!env.exists(&validator_index_pda(GAME, 2)).await);
+    assert_eq!(env.lamports(&idle).await, wallet_before + MIN_VALIDATOR_STAKE);
+    let entry: ValidatorIndex = env.account(&validator_index_pda(GAME, 0)).await;
+    assert_eq!(entry.validator, last);
+    assert_eq!(env.validator(GAME, &last).await.index, 0);
+
+    let game = env.game(GAME).await;
+    assert_eq!(game.validator_count, 2);
+    assert_eq!(game.total_stake, 2 * MIN_VALIDATOR_STAKE);
+}
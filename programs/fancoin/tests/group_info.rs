@@ -0,0 +1,69 @@
+mod common;
+
+use common::*;
+use fancoin::{group_assignment, ErrorCode, GroupAssignment, MAX_GROUP_QUERY_VALIDATORS};
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::TransactionError,
+};
+
+const GAME: u32 = 1;
+
+fn group_info_ix(validators: Vec<Pubkey>) -> Instruction {
+    let accounts = fancoin::accounts::GetValidatorGroupInfo { game: game_pda(GAME) };
+    instruction(accounts, fancoin::instruction::GetValidatorGroupInfo { game_number: GAME, validators })
+}
+
+fn assert_simulated_error<T>(result: Result<T, TransactionError>, code: ErrorCode) {
+    let expected = code as u32 + anchor_lang::error::ERROR_CODE_OFFSET;
+    match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code))) => assert_eq!(code, expected),
+        Err(other) => panic!("expected custom error {}, got {:?}", expected, other),
+        Ok(_) => panic!("expected custom error {}, but the simulation succeeded", expected),
+    }
+}
+
+#[tokio::test]
+async fn group_info_matches_the_off_chain_assignment() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let first = env.add_validator(GAME).await;
+    let second = env.add_validator(GAME).await;
+    let keys = vec![first.pubkey(), second.pubkey(), Pubkey::new_unique()];
+
+    // Groups only exist once a seed is finalized
+    let before_seed = env.simulate_return::<GroupAssignment>(&[group_info_ix(keys.clone())]).await;
+    assert_simulated_error(before_seed, ErrorCode::NoSeed);
+
+    env.seed_round(0, &[(GAME, &[&first, &second])]).await;
+    let game = env.game(GAME).await;
+    let seed = game.last_seed.unwrap();
+
+    let assignment: GroupAssignment = env.simulate_return(&[group_info_ix(keys.clone())]).await.unwrap();
+    let expected = group_assignment(&keys, seed, game.validator_count, game.grouping_strategy).unwrap();
+    assert_eq!(assignment, expected);
+    assert_eq!(assignment.seed, seed);
+    assert_eq!(assignment.group_ids.len(), keys.len());
+    assert_eq!(assignment.compatible.len(), 3);
+    assert!(assignment.is_compatible(1, 1));
+    assert_eq!(assignment.is_compatible(2, 0), assignment.compatible[1]);
+}
+
+#[tokio::test]
+async fn group_info_queries_are_bounded() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validator = env.add_validator(GAME).await;
+    env.seed_round(0, &[(GAME, &[&validator])]).await;
+
+    let full: Vec<Pubkey> = (0..MAX_GROUP_QUERY_VALIDATORS).map(|_| Pubkey::new_unique()).collect();
+    let assignment: GroupAssignment = env.simulate_return(&[group_info_ix(full.clone())]).await.unwrap();
+    assert_eq!(assignment.group_ids.len(), MAX_GROUP_QUERY_VALIDATORS);
+
+    let mut too_many = full;
+    too_many.push(Pubkey::new_unique());
+    let result = env.simulate_return::<GroupAssignment>(&[group_info_ix(too_many)]).await;
+    assert_simulated_error(result, ErrorCode::InvalidRange);
+}
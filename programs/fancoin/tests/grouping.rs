@@ -0,0 +1,44 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, DEFAULT_GROUP_SIZE};
+use solana_sdk::signer::Signer;
+
+const GAME: u32 = 1;
+
+#[tokio::test]
+async fn new_games_start_bucketed_so_honest_validators_agree() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    assert_eq!(
+        env.game(GAME).await.grouping_strategy,
+        GroupingStrategy::Bucketed { group_size: DEFAULT_GROUP_SIZE }
+    );
+
+    // Two validators share the only bucket, so the default resolves agreements
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+    assert_eq!(env.game(GAME).await.pending_payouts.len(), 1);
+    assert_eq!(env.validator(GAME, &validators[0].pubkey()).await.failed_approvals, 0);
+}
+
+#[tokio::test]
+async fn degenerate_strategies_are_rejected() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+
+    for grouping_strategy in [GroupingStrategy::Bucketed { group_size: 1 }, GroupingStrategy::Ring { radius: 0 }] {
+        let accounts = env.update_game(GAME);
+        let args = fancoin::instruction::SetGroupingStrategy { game_number: GAME, grouping_strategy };
+        assert_error(env.send(&[instruction(accounts, args)], &[]).await, ErrorCode::InvalidGroupingStrategy);
+    }
+
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 1 }).await;
+    assert_eq!(env.game(GAME).await.grouping_strategy, GroupingStrategy::Ring { radius: 1 });
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GameStatus};
+use solana_program_test::BanksClientError;
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const GAME: u32 = 1;
+
+async fn set_status(
+    env: &mut TestEnv,
+    signer: Option<&Keypair>,
+    new_status: GameStatus,
+    reason: &str,
+) -> Result<(), BanksClientError> {
+    let mut accounts = env.update_game(GAME);
+    let signers: Vec<&Keypair> = signer.into_iter().collect();
+    if let Some(signer) = signer {
+        accounts.signer = signer.pubkey();
+    }
+    let args = fancoin::instruction::UpdateGameStatus {
+        game_number: GAME,
+        new_status,
+        description: "game".to_string(),
+        reason: reason.to_string(),
+    };
+    env.send(&[instruction(accounts, args)], &signers).await
+}
+
+#[tokio::test]
+async fn status_changes_follow_the_transition_table() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    assert_eq!(env.game(GAME).await.status, GameStatus::Probationary);
+
+    assert_error(
+        set_status(&mut env, None, GameStatus::Probationary, "").await,
+        ErrorCode::GameStatusAlreadySet,
+    );
+    assert_error(
+        set_status(&mut env, None, GameStatus::Retired, "").await,
+        ErrorCode::InvalidStatusTransition,
+    );
+    let long_reason = "x".repeat(fancoin::MAX_STATUS_REASON_LEN + 1);
+    assert_error(
+        set_status(&mut env, None, GameStatus::Blacklisted, &long_reason).await,
+        ErrorCode::ReasonTooLong,
+    );
+
+    set_status(&mut env, None, GameStatus::Blacklisted, "cheating").await.unwrap();
+    // Reinstated games go back on probation first
+    assert_error(
+        set_status(&mut env, None, GameStatus::Whitelisted, "").await,
+        ErrorCode::InvalidStatusTransition,
+    );
+    set_status(&mut env, None, GameStatus::Probationary, "appeal").await.unwrap();
+    set_status(&mut env, None, GameStatus::Whitelisted, "").await.unwrap();
+    assert_eq!(env.game(GAME).await.status, GameStatus::Whitelisted);
+}
+
+#[tokio::test]
+async fn creators_cannot_whitelist_or_blacklist() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    env.create_game_for(GAME, &creator.pubkey()).await;
+
+    assert_error(
+        set_status(&mut env, Some(&creator), GameStatus::Whitelisted, "").await,
+        ErrorCode::Unauthorized,
+    );
+    set_status(&mut env, None, GameStatus::Whitelisted, "").await.unwrap();
+    assert_error(
+        set_status(&mut env, Some(&creator), GameStatus::Probationary, "").await,
+        ErrorCode::Unauthorized,
+    );
+
+    let stranger = env.funded_keypair().await;
+    assert_error(
+        set_status(&mut env, Some(&stranger), GameStatus::Probationary, "").await,
+        ErrorCode::Unauthorized,
+    );
+}
+
+#[test]
+fn error_codes_keep_their_numbers() {
+    // Clients decode errors by number, so retired variants keep their slot
+    assert_eq!(ErrorCode::GameIsBlacklisted as u32, 10);
+    assert_eq!(ErrorCode::GameNotWhitelisted as u32, 11);
+    assert_eq!(ErrorCode::NameChangeCooldown as u32, 12);
+    assert_eq!(ErrorCode::AlreadyExpanded as u32, 16);
+    assert_eq!(ErrorCode::Overflow as u32, 17);
+}
+
+#[test]
+fn rewards_scale_with_the_status() {
+    assert_eq!(GameStatus::Whitelisted.scale_reward(1_000), 1_000);
+    assert_eq!(
+        GameStatus::Probationary.scale_reward(1_000),
+        1_000 * fancoin::PROBATIONARY_REWARD_BPS / 10_000
+    );
+    assert_eq!(GameStatus::Blacklisted.scale_reward(1_000), 0);
+    assert_eq!(GameStatus::Retired.scale_reward(1_000), 0);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GameMetadata, MAX_GAME_SERVERS};
+use solana_program_test::BanksClientError;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+async fn set_metadata(env: &mut TestEnv, signer: &Keypair, name: String, uri: String) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::SetGameMetadata {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        metadata: metadata_pda(GAME),
+        signer: signer.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::SetGameMetadata { game_number: GAME, name, uri };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+fn servers_accounts(signer: &Pubkey) -> fancoin::accounts::UpdateGameServers {
+    fancoin::accounts::UpdateGameServers {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        metadata: metadata_pda(GAME),
+        signer: *signer,
+    }
+}
+
+async fn add_server(env: &mut TestEnv, endpoint: &str, signing_key: Pubkey) -> Result<(), BanksClientError> {
+    let accounts = servers_accounts(&env.owner.pubkey());
+    let args = fancoin::instruction::AddGameServer { game_number: GAME, endpoint: endpoint.to_string(), signing_key };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+async fn remove_server(env: &mut TestEnv, signing_key: Pubkey) -> Result<(), BanksClientError> {
+    let accounts = servers_accounts(&env.owner.pubkey());
+    let args = fancoin::instruction::RemoveGameServer { game_number: GAME, signing_key };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+#[tokio::test]
+async fn metadata_is_length_checked_and_creator_editable() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    env.create_game_for(GAME, &creator.pubkey()).await;
+
+    let long_name = "n".repeat(fancoin::MAX_GAME_NAME_LEN + 1);
+    assert_error(set_metadata(&mut env, &creator, long_name, String::new()).await, ErrorCode::NameTooLong);
+    let long_uri = "u".repeat(fancoin::MAX_METADATA_URI_LEN + 1);
+    assert_error(set_metadata(&mut env, &creator, "Game".to_string(), long_uri).await, ErrorCode::UriTooLong);
+    let stranger = env.funded_keypair().await;
+    assert_error(
+        set_metadata(&mut env, &stranger, "Game".to_string(), String::new()).await,
+        ErrorCode::Unauthorized,
+    );
+
+    let uri = "https://example.invalid/game.json".to_string();
+    set_metadata(&mut env, &creator, "Game".to_string(), uri.clone()).await.unwrap();
+    let metadata: GameMetadata = env.account(&metadata_pda(GAME)).await;
+    assert_eq!((metadata.name.as_str(), metadata.uri), ("Game", uri));
+    assert!(metadata.servers.is_empty());
+}
+
+#[tokio::test]
+async fn the_server_registry_is_bounded_and_unique() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let owner = env.owner.insecure_clone();
+    set_metadata(&mut env, &owner, "Game".to_string(), String::new()).await.unwrap();
+
+    let long_endpoint = "e".repeat(fancoin::MAX_SERVER_ENDPOINT_LEN + 1);
+    assert_error(add_server(&mut env, &long_endpoint, Pubkey::new_unique()).await, ErrorCode::EndpointTooLong);
+
+    let keys: Vec<Pubkey> = (0..MAX_GAME_SERVERS).map(|_| Pubkey::new_unique()).collect();
+    for key in &keys {
+        add_server(&mut env, "https://example.invalid", *key).await.unwrap();
+    }
+    assert_error(
+        add_server(&mut env, "https://example.invalid", keys[0]).await,
+        ErrorCode::ServerAlreadyRegistered,
+    );
+    assert_error(
+        add_server(&mut env, "https://example.invalid", Pubkey::new_unique()).await,
+        ErrorCode::TooManyServers,
+    );
+
+    remove_server(&mut env, keys[0]).await.unwrap();
+    assert_error(remove_server(&mut env, keys[0]).await, ErrorCode::ServerNotFound);
+    let metadata: GameMetadata = env.account(&metadata_pda(GAME)).await;
+    assert_eq!(metadata.servers.len(), MAX_GAME_SERVERS - 1);
+}
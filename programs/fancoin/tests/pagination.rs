@@ -0,0 +1,78 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, PdaPage};
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signer::Signer,
+    transaction::TransactionError,
+};
+
+const GAME: u32 = 1;
+
+fn player_page_ix(start_index: u32, end_index: u32, players: &[Pubkey]) -> Instruction {
+    let accounts = fancoin::accounts::GetPlayerListPdaPage { dapp: dapp_pda() };
+    let mut ix = instruction(accounts, fancoin::instruction::GetPlayerListPdaPage { start_index, end_index });
+    ix.accounts.extend(players.iter().map(|key| AccountMeta::new_readonly(*key, false)));
+    ix
+}
+
+fn assert_simulated_error<T>(result: Result<T, TransactionError>, code: ErrorCode) {
+    let expected = code as u32 + anchor_lang::error::ERROR_CODE_OFFSET;
+    match result {
+        Err(TransactionError::InstructionError(_, InstructionError::Custom(code))) => assert_eq!(code, expected),
+        Err(other) => panic!("expected custom error {}, got {:?}", expected, other),
+        Ok(_) => panic!("expected custom error {}, but the simulation succeeded", expected),
+    }
+}
+
+#[tokio::test]
+async fn player_pages_are_returned_with_a_cursor() {
+    let mut env = TestEnv::start().await;
+    let players = [
+        env.register_player("alice").await,
+        env.register_player("bob").await,
+        env.register_player("carol").await,
+    ];
+    let keys: Vec<Pubkey> = players.iter().map(|p| player_pda(p.index)).collect();
+
+    let page: PdaPage = env.simulate_return(&[player_page_ix(0, 2, &keys[..2])]).await.unwrap();
+    let names: Vec<&str> = page.entries.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["alice", "bob"]);
+    assert_eq!(page.entries[1].key, keys[1]);
+    assert_eq!(page.entries[1].reward_address, players[1].authority.pubkey());
+    assert_eq!(page.next_index, Some(2));
+
+    // The end is clamped to the player count
+    let page: PdaPage = env.simulate_return(&[player_page_ix(2, 10, &keys[2..])]).await.unwrap();
+    assert_eq!(page.entries.len(), 1);
+    assert_eq!(page.next_index, None);
+
+    let out_of_order = player_page_ix(0, 2, &[keys[1], keys[0]]);
+    assert_simulated_error(env.simulate_return::<PdaPage>(&[out_of_order]).await, ErrorCode::InvalidRange);
+    let past_the_end = player_page_ix(3, 4, &[]);
+    assert_simulated_error(env.simulate_return::<PdaPage>(&[past_the_end]).await, ErrorCode::InvalidRange);
+}
+
+#[tokio::test]
+async fn validator_pages_report_liveness() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+
+    let accounts = fancoin::accounts::GetValidatorListPdaPage { game: game_pda(GAME) };
+    let args = fancoin::instruction::GetValidatorListPdaPage { game_number: GAME, start_index: 0, end_index: 2 };
+    let mut ix = instruction(accounts, args);
+    for validator in &validators {
+        ix.accounts.push(AccountMeta::new_readonly(validator_pda(GAME, &validator.pubkey()), false));
+    }
+    let page: PdaPage = env.simulate_return(&[ix]).await.unwrap();
+
+    assert_eq!(page.next_index, None);
+    for (entry, validator) in page.entries.iter().zip(&validators) {
+        assert_eq!(entry.reward_address, validator.pubkey());
+        assert_eq!(entry.last_activity, Some(START));
+        assert!(entry.name.is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, PlayerGameState};
+use solana_sdk::{signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+/// Game with two validators in every player's group, alice joined, past its seed.
+async fn minting_game(env: &mut TestEnv) -> ([Keypair; 2], Player, Keypair) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    (validators, alice, server)
+}
+
+#[tokio::test]
+async fn players_are_minted_at_most_once_per_epoch() {
+    let mut env = TestEnv::start().await;
+    let (validators, alice, server) = minting_game(&mut env).await;
+
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+    let state: PlayerGameState = env.account(&player_game_pda(GAME, alice.id)).await;
+    let payout = env.game(GAME).await.pending_payouts[0].clone();
+    assert_eq!(state.last_minted_epoch, Some(0));
+    assert_eq!(state.total_minted, payout.player_reward);
+    assert_eq!(state.lifetime_approvals, 2);
+
+    // A second agreement in the same epoch is dropped once it would resolve
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+    let game = env.game(GAME).await;
+    assert_eq!(game.pending_payouts.len(), 1);
+    assert!(game.minting_agreements.is_empty());
+    let state: PlayerGameState = env.account(&player_game_pda(GAME, alice.id)).await;
+    assert_eq!(state.lifetime_approvals, 2);
+}
+
+#[tokio::test]
+async fn each_attested_player_needs_its_state_once() {
+    let mut env = TestEnv::start().await;
+    let (validators, alice, server) = minting_game(&mut env).await;
+    let validator = &validators[0];
+
+    let mut ixs = TestEnv::submit_ixs(GAME, 0, &server, &validator.pubkey(), &[&alice]);
+    let state = ixs[1].accounts.pop().unwrap();
+    assert_error(env.send(&ixs, &[validator]).await, ErrorCode::MissingPlayerGameState);
+
+    ixs[1].accounts.push(state.clone());
+    ixs[1].accounts.push(state);
+    assert_error(env.send(&ixs, &[validator]).await, ErrorCode::DuplicatePlayerAccount);
+
+    // Only one state per player and game
+    let accounts = fancoin::accounts::InitPlayerGameState {
+        game: game_pda(GAME),
+        player_pda: player_pda(alice.index),
+        player_game_state: player_game_pda(GAME, alice.id),
+        user: env.owner.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::InitPlayerGameState { game_number: GAME, player_index: alice.index };
+    assert!(env.send(&[instruction(accounts, args)], &[]).await.is_err());
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::*;
+use fancoin::{DApp, ErrorCode, ProposalAction, PROPOSAL_TTL_SLOTS};
+use solana_program_test::BanksClientError;
+use solana_sdk::{clock::Clock, pubkey::Pubkey, signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+/// Propose `action` as `proposer`; returns the proposal id.
+async fn propose(env: &mut TestEnv, proposer: &Keypair, action: ProposalAction) -> u64 {
+    let dapp: DApp = env.account(&dapp_pda()).await;
+    let accounts = fancoin::accounts::ProposeAction {
+        dapp: dapp_pda(),
+        proposal: proposal_pda(dapp.proposal_count),
+        proposer: proposer.pubkey(),
+        system_program: system_program::ID,
+    };
+    let ix = instruction(accounts, fancoin::instruction::ProposeAction { action });
+    env.send(&[ix], &[proposer]).await.unwrap();
+    dapp.proposal_count
+}
+
+async fn approve(env: &mut TestEnv, proposal_id: u64, owner: &Keypair) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ApproveProposal {
+        dapp: dapp_pda(),
+        proposal: proposal_pda(proposal_id),
+        owner: owner.pubkey(),
+    };
+    let ix = instruction(accounts, fancoin::instruction::ApproveProposal { proposal_id });
+    env.send(&[ix], &[owner]).await
+}
+
+async fn execute(
+    env: &mut TestEnv,
+    proposal_id: u64,
+    proposer: &Pubkey,
+    new_game: Option<Pubkey>,
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ExecuteProposal {
+        dapp: dapp_pda(),
+        proposal: proposal_pda(proposal_id),
+        game: None,
+        new_game,
+        player_game_state: None,
+        proposer: *proposer,
+        executor: env.owner.pubkey(),
+        system_program: system_program::ID,
+    };
+    let ix = instruction(accounts, fancoin::instruction::ExecuteProposal { proposal_id });
+    env.send(&[ix], &[]).await
+}
+
+/// Add `second` as an owner and require both owners from then on.
+async fn two_of_two(env: &mut TestEnv, second: &Keypair) {
+    let owner = env.owner.insecure_clone();
+    let id = propose(env, &owner, ProposalAction::AddOwner { owner: second.pubkey() }).await;
+    execute(env, id, &owner.pubkey(), None).await.unwrap();
+    let id = propose(env, &owner, ProposalAction::SetThreshold { threshold: 2 }).await;
+    execute(env, id, &owner.pubkey(), None).await.unwrap();
+}
+
+#[tokio::test]
+async fn owner_actions_need_the_threshold_of_approvals() {
+    let mut env = TestEnv::start().await;
+    let owner = env.owner.insecure_clone();
+    let second = env.funded_keypair().await;
+    two_of_two(&mut env, &second).await;
+
+    // Nobody acts alone any more
+    let accounts = fancoin::accounts::InitializeGame {
+        game: game_pda(GAME),
+        dapp: dapp_pda(),
+        user: owner.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::InitializeGame {
+        game_number: GAME,
+        description: String::new(),
+        creator: owner.pubkey(),
+    };
+    assert_error(env.send(&[instruction(accounts, args)], &[]).await, ErrorCode::Unauthorized);
+
+    let action = ProposalAction::CreateGame {
+        game_number: GAME,
+        description: "by proposal".to_string(),
+        creator: owner.pubkey(),
+    };
+    let id = propose(&mut env, &owner, action).await;
+    assert_error(execute(&mut env, id, &owner.pubkey(), Some(game_pda(GAME))).await, ErrorCode::ThresholdNotMet);
+    assert_error(approve(&mut env, id, &owner).await, ErrorCode::AlreadyApproved);
+    let stranger = env.funded_keypair().await;
+    assert_error(approve(&mut env, id, &stranger).await, ErrorCode::Unauthorized);
+
+    approve(&mut env, id, &second).await.unwrap();
+    assert_error(execute(&mut env, id, &owner.pubkey(), None).await, ErrorCode::MissingGameAccount);
+    execute(&mut env, id, &owner.pubkey(), Some(game_pda(GAME))).await.unwrap();
+    assert_eq!(env.game(GAME).await.description, "by proposal");
+    assert!(!env.exists(&proposal_pda(id)).await);
+}
+
+#[tokio::test]
+async fn only_create_game_proposals_take_a_new_game() {
+    let mut env = TestEnv::start().await;
+    let owner = env.owner.insecure_clone();
+    let id = propose(&mut env, &owner, ProposalAction::SetRewardChangeDelay { delay: 60 }).await;
+
+    // The account would be initialized as a zeroed game 0
+    assert_error(
+        execute(&mut env, id, &owner.pubkey(), Some(game_pda(0))).await,
+        ErrorCode::UnexpectedGameAccount,
+    );
+    assert!(!env.exists(&game_pda(0)).await);
+
+    execute(&mut env, id, &owner.pubkey(), None).await.unwrap();
+    let dapp: DApp = env.account(&dapp_pda()).await;
+    assert_eq!(dapp.reward_change_delay, 60);
+}
+
+#[tokio::test]
+async fn proposals_expire() {
+    let mut env = TestEnv::start().await;
+    let owner = env.owner.insecure_clone();
+    let id = propose(&mut env, &owner, ProposalAction::SetRewardChangeDelay { delay: 60 }).await;
+
+    let mut clock: Clock = env.ctx.banks_client.get_sysvar().await.unwrap();
+    clock.slot += PROPOSAL_TTL_SLOTS + 1;
+    env.ctx.set_sysvar(&clock);
+    assert_error(execute(&mut env, id, &owner.pubkey(), None).await, ErrorCode::ProposalExpired);
+}
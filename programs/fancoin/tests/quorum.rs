@@ -0,0 +1,97 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, QuorumPolicy, MIN_VALIDATOR_STAKE};
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::Keypair;
+
+const GAME: u32 = 1;
+
+async fn set_quorum(env: &mut TestEnv, quorum_policy: QuorumPolicy) -> Result<(), BanksClientError> {
+    let accounts = env.update_game(GAME);
+    let args = fancoin::instruction::SetQuorumPolicy { game_number: GAME, quorum_policy };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+/// Game where every validator is in every player's group, with one validator per stake.
+async fn game_with_stakes(env: &mut TestEnv, stakes: &[u64]) -> (Vec<Keypair>, Player, Keypair) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let mut validators = Vec::new();
+    for stake in stakes {
+        let validator = env.funded_keypair().await;
+        env.register_validator(GAME, &validator, *stake).await;
+        validators.push(validator);
+    }
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+    (validators, alice, server)
+}
+
+#[tokio::test]
+async fn invalid_quorum_policies_are_rejected() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    for policy in [
+        QuorumPolicy::Absolute { count: 1 },
+        QuorumPolicy::Absolute { count: fancoin::MAX_AGREEMENT_VALIDATORS as u32 + 1 },
+        QuorumPolicy::Fraction { numerator: 0, denominator: 3 },
+        QuorumPolicy::Fraction { numerator: 4, denominator: 3 },
+        QuorumPolicy::StakeWeighted { threshold_bps: 0 },
+        QuorumPolicy::StakeWeighted { threshold_bps: 10_001 },
+    ] {
+        assert_error(set_quorum(&mut env, policy).await, ErrorCode::InvalidQuorumPolicy);
+    }
+    set_quorum(&mut env, QuorumPolicy::Fraction { numerator: 2, denominator: 3 }).await.unwrap();
+}
+
+#[tokio::test]
+async fn absolute_quorum_waits_for_the_count() {
+    let mut env = TestEnv::start().await;
+    let stakes = [MIN_VALIDATOR_STAKE; 3];
+    let (validators, alice, server) = game_with_stakes(&mut env, &stakes).await;
+    set_quorum(&mut env, QuorumPolicy::Absolute { count: 3 }).await.unwrap();
+    let signers: Vec<&Keypair> = validators.iter().collect();
+    env.seed_round(0, &[(GAME, &signers)]).await;
+
+    for validator in &validators[..2] {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+    let game = env.game(GAME).await;
+    assert_eq!(game.minting_agreements[0].validators.len(), 2);
+    assert!(game.pending_payouts.is_empty());
+
+    env.submit(GAME, 0, &server, &validators[2], &[&alice]).await.unwrap();
+    let game = env.game(GAME).await;
+    assert!(game.minting_agreements.is_empty());
+    assert_eq!(game.pending_payouts.len(), 1);
+}
+
+#[tokio::test]
+async fn stake_weighted_quorum_counts_stake_not_heads() {
+    let mut env = TestEnv::start().await;
+    let stakes = [MIN_VALIDATOR_STAKE, MIN_VALIDATOR_STAKE, 3 * MIN_VALIDATOR_STAKE];
+    let (validators, alice, server) = game_with_stakes(&mut env, &stakes).await;
+    set_quorum(&mut env, QuorumPolicy::StakeWeighted { threshold_bps: 5_000 }).await.unwrap();
+    let signers: Vec<&Keypair> = validators.iter().collect();
+    env.seed_round(0, &[(GAME, &signers)]).await;
+
+    // Two of three validators, but only 40% of the stake
+    for validator in &validators[..2] {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+    assert!(env.game(GAME).await.pending_payouts.is_empty());
+
+    env.submit(GAME, 0, &server, &validators[2], &[&alice]).await.unwrap();
+    assert_eq!(env.game(GAME).await.pending_payouts.len(), 1);
+}
+
+#[test]
+fn fraction_quorum_rounds_up_and_is_capped() {
+    let two_thirds = QuorumPolicy::Fraction { numerator: 2, denominator: 3 };
+    assert!(!two_thirds.is_met(2, 0, 4, 0));
+    assert!(two_thirds.is_met(3, 0, 4, 0));
+    let all = QuorumPolicy::Fraction { numerator: 1, denominator: 1 };
+    assert!(all.is_met(fancoin::MAX_AGREEMENT_VALIDATORS, 0, 1_000, 0));
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GameStatus};
+use solana_program_test::BanksClientError;
+use solana_sdk::{signature::Keypair, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+
+async fn retire(env: &mut TestEnv) -> Result<(), BanksClientError> {
+    let accounts = env.update_game(GAME);
+    let args = fancoin::instruction::RetireGame { game_number: GAME, reason: "sunset".to_string() };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+async fn withdraw(env: &mut TestEnv, validator: &Keypair) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::WithdrawValidator {
+        game: game_pda(GAME),
+        validator_pda: validator_pda(GAME, &validator.pubkey()),
+        validator_index: validator_index_pda(GAME, 0),
+        last_validator_index: None,
+        moved_validator_pda: None,
+        validator: validator.pubkey(),
+    };
+    let ix = instruction(accounts, fancoin::instruction::WithdrawValidator { game_number: GAME });
+    env.send(&[ix], &[validator]).await
+}
+
+async fn close(env: &mut TestEnv, creator: &Keypair) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::CloseGame {
+        game: game_pda(GAME),
+        metadata: metadata_pda(GAME),
+        creator: creator.pubkey(),
+        dapp: dapp_pda(),
+        signer: creator.pubkey(),
+    };
+    let ix = instruction(accounts, fancoin::instruction::CloseGame { game_number: GAME });
+    env.send(&[ix], &[creator]).await
+}
+
+async fn try_create_game(env: &mut TestEnv, game_number: u32) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::InitializeGame {
+        game: game_pda(game_number),
+        dapp: dapp_pda(),
+        user: env.owner.pubkey(),
+        system_program: system_program::ID,
+    };
+    let args = fancoin::instruction::InitializeGame {
+        game_number,
+        description: String::new(),
+        creator: env.owner.pubkey(),
+    };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+#[tokio::test]
+async fn retired_games_close_once_validators_have_left() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    env.create_game_for(GAME, &creator.pubkey()).await;
+    let validator = env.add_validator(GAME).await;
+
+    assert_error(withdraw(&mut env, &validator).await, ErrorCode::GameNotRetired);
+    assert_error(close(&mut env, &creator).await, ErrorCode::GameNotRetired);
+
+    retire(&mut env).await.unwrap();
+    assert_eq!(env.game(GAME).await.status, GameStatus::Retired);
+    assert_error(retire(&mut env).await, ErrorCode::GameStatusAlreadySet);
+    let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), 0);
+    assert_error(env.send(&[ix], &[&validator]).await, ErrorCode::GameIsRetired);
+    assert_error(close(&mut env, &creator).await, ErrorCode::GameHasValidators);
+
+    let wallet_before = env.lamports(&validator.pubkey()).await;
+    withdraw(&mut env, &validator).await.unwrap();
+    assert!(env.lamports(&validator.pubkey()).await > wallet_before + fancoin::MIN_VALIDATOR_STAKE);
+    assert_eq!(env.game(GAME).await.validator_count, 0);
+
+    let game_rent = env.lamports(&game_pda(GAME)).await;
+    let creator_before = env.lamports(&creator.pubkey()).await;
+    close(&mut env, &creator).await.unwrap();
+    assert!(!env.exists(&game_pda(GAME)).await);
+    assert_eq!(env.lamports(&creator.pubkey()).await, creator_before + game_rent);
+}
+
+#[tokio::test]
+async fn game_numbers_are_never_reused() {
+    let mut env = TestEnv::start().await;
+    let creator = env.funded_keypair().await;
+    env.create_game_for(GAME, &creator.pubkey()).await;
+    retire(&mut env).await.unwrap();
+    close(&mut env, &creator).await.unwrap();
+
+    // Player state keyed by the closed game's number must not apply to a new game
+    assert_error(try_create_game(&mut env, GAME).await, ErrorCode::GameNumberUsed);
+    assert_error(try_create_game(&mut env, GAME - 1).await, ErrorCode::GameNumberUsed);
+    try_create_game(&mut env, GAME + 1).await.unwrap();
+    let dapp: fancoin::DApp = env.account(&dapp_pda()).await;
+    assert_eq!(dapp.next_game_number, GAME + 2);
+}
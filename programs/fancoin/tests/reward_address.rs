@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, PlayerPda, DEFAULT_REWARD_CHANGE_DELAY};
+use solana_program_test::BanksClientError;
+use solana_sdk::{pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+const ONE_WEEK: i64 = 7 * 24 * 3600;
+
+async fn request(env: &mut TestEnv, player: &Player, signer: &Keypair, new_reward: Pubkey) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ChangePlayerReward {
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        user: signer.pubkey(),
+    };
+    let args = fancoin::instruction::RequestRewardAddressChange { new_reward };
+    env.send(&[instruction(accounts, args)], &[signer]).await
+}
+
+async fn cancel(env: &mut TestEnv, player: &Player) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::ChangePlayerReward {
+        dapp: dapp_pda(),
+        player_pda: player_pda(player.index),
+        user: player.authority.pubkey(),
+    };
+    let args = fancoin::instruction::CancelRewardAddressChange {};
+    env.send(&[instruction(accounts, args)], &[&player.authority]).await
+}
+
+async fn finalize(env: &mut TestEnv, player: &Player) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::FinalizePlayerReward { dapp: dapp_pda(), player_pda: player_pda(player.index) };
+    let args = fancoin::instruction::FinalizeRewardAddressChange {};
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+#[tokio::test]
+async fn reward_changes_wait_out_the_timelock() {
+    let mut env = TestEnv::start().await;
+    let alice = env.register_player("alice").await;
+    let new_reward = Pubkey::new_unique();
+
+    let thief = Keypair::new();
+    assert_error(request(&mut env, &alice, &thief, new_reward).await, ErrorCode::Unauthorized);
+    assert_error(finalize(&mut env, &alice).await, ErrorCode::NoRewardChangePending);
+
+    request(&mut env, &alice, &alice.authority, new_reward).await.unwrap();
+    assert_error(
+        request(&mut env, &alice, &alice.authority, Pubkey::new_unique()).await,
+        ErrorCode::RewardChangePending,
+    );
+    env.set_time(START + DEFAULT_REWARD_CHANGE_DELAY - 1).await;
+    assert_error(finalize(&mut env, &alice).await, ErrorCode::RewardChangeTimelocked);
+
+    env.set_time(START + DEFAULT_REWARD_CHANGE_DELAY).await;
+    finalize(&mut env, &alice).await.unwrap();
+    let player: PlayerPda = env.account(&player_pda(alice.index)).await;
+    assert_eq!(player.reward_address, new_reward);
+    assert_eq!(player.pending_reward_address, None);
+
+    // Completed changes are still a week apart
+    assert_error(
+        request(&mut env, &alice, &alice.authority, Pubkey::new_unique()).await,
+        ErrorCode::RewardChangeCooldown,
+    );
+    env.set_time(START + DEFAULT_REWARD_CHANGE_DELAY + ONE_WEEK).await;
+    request(&mut env, &alice, &alice.authority, Pubkey::new_unique()).await.unwrap();
+}
+
+#[tokio::test]
+async fn the_authority_can_cancel_a_pending_change() {
+    let mut env = TestEnv::start().await;
+    let alice = env.register_player("alice").await;
+    assert_error(cancel(&mut env, &alice).await, ErrorCode::NoRewardChangePending);
+
+    request(&mut env, &alice, &alice.authority, Pubkey::new_unique()).await.unwrap();
+    cancel(&mut env, &alice).await.unwrap();
+
+    env.set_time(START + DEFAULT_REWARD_CHANGE_DELAY).await;
+    assert_error(finalize(&mut env, &alice).await, ErrorCode::NoRewardChangePending);
+    let player: PlayerPda = env.account(&player_pda(alice.index)).await;
+    assert_eq!(player.reward_address, alice.authority.pubkey());
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, RewardConfig, ValidatorSplit};
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::Keypair;
+
+const GAME: u32 = 1;
+
+async fn set_rewards(env: &mut TestEnv, reward_config: RewardConfig) -> Result<(), BanksClientError> {
+    let accounts = env.update_game(GAME);
+    let args = fancoin::instruction::SetRewardConfig { game_number: GAME, reward_config };
+    env.send(&[instruction(accounts, args)], &[]).await
+}
+
+/// Probationary game with two validators in every player's group and two players.
+async fn rewarded_game(env: &mut TestEnv, reward_config: RewardConfig) -> ([Keypair; 2], [Player; 2], Keypair) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    set_rewards(env, reward_config).await.unwrap();
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let server = env.add_server(GAME).await;
+    let players = [env.register_player("alice").await, env.register_player("bob").await];
+    for player in &players {
+        env.join_game(GAME, player).await;
+    }
+    env.seed_round(0, &[(GAME, &[&validators[0], &validators[1]])]).await;
+    (validators, players, server)
+}
+
+#[tokio::test]
+async fn invalid_reward_configs_are_rejected() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+
+    let too_generous = RewardConfig { creator_share_bps: 10_001, ..RewardConfig::default() };
+    assert_error(set_rewards(&mut env, too_generous).await, ErrorCode::InvalidRewardConfig);
+    let overflowing = RewardConfig { player_amount: u64::MAX, validator_amount: 1, ..RewardConfig::default() };
+    assert_error(set_rewards(&mut env, overflowing).await, ErrorCode::Overflow);
+}
+
+#[tokio::test]
+async fn payouts_follow_the_reward_config_and_status() {
+    let mut env = TestEnv::start().await;
+    let reward_config = RewardConfig {
+        player_amount: 1_000,
+        validator_amount: 300,
+        validator_split: ValidatorSplit::ByApprovalOrder,
+        epoch_emission_cap: u64::MAX,
+        creator_share_bps: 1_000,
+    };
+    let (validators, [alice, _], server) = rewarded_game(&mut env, reward_config).await;
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&alice]).await.unwrap();
+    }
+
+    // Probationary games pay half, and the creator takes 10% of that
+    let game = env.game(GAME).await;
+    let payout = &game.pending_payouts[0];
+    assert_eq!(payout.player_reward, 450);
+    assert_eq!(payout.validator_shares, vec![90, 45]);
+    assert_eq!(payout.creator_reward, 65);
+    assert_eq!((game.emission_epoch, game.epoch_player_emission, game.epoch_validator_emission), (0, 450, 135));
+    assert_eq!(game.epoch_creator_emission, 65);
+}
+
+#[tokio::test]
+async fn agreements_over_the_epoch_cap_wait() {
+    let mut env = TestEnv::start().await;
+    let reward_config = RewardConfig {
+        player_amount: 1_000,
+        validator_amount: 1_000,
+        validator_split: ValidatorSplit::Even,
+        // One probationary agreement emits 1_000
+        epoch_emission_cap: 1_500,
+        creator_share_bps: 0,
+    };
+    let (validators, [alice, bob], server) = rewarded_game(&mut env, reward_config).await;
+    for validator in &validators {
+        env.submit(GAME, 0, &server, validator, &[&alice, &bob]).await.unwrap();
+    }
+
+    let game = env.game(GAME).await;
+    assert_eq!(game.pending_payouts.len(), 1);
+    assert_eq!(game.minting_agreements.len(), 1);
+    assert_eq!(game.epoch_player_emission + game.epoch_validator_emission, 1_000);
+}
+
+#[test]
+fn validator_shares_add_up_to_the_pool() {
+    assert_eq!(ValidatorSplit::Even.shares(3, 100), vec![34, 33, 33]);
+    assert_eq!(ValidatorSplit::ByApprovalOrder.shares(3, 60), vec![30, 20, 10]);
+    assert!(ValidatorSplit::Even.shares(0, 100).is_empty());
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, SEED_SLOT_DELAY};
+use solana_sdk::{signature::Keypair, signer::Signer};
+
+const GAME: u32 = 1;
+
+/// Game with two validators that have committed and revealed for epoch 0, with the
+/// clock at the start of the mint window.
+async fn revealed_game(env: &mut TestEnv) -> [Keypair; 2] {
+    env.create_game(GAME).await;
+    let validators = [env.add_validator(GAME).await, env.add_validator(GAME).await];
+    let game = env.game(GAME).await;
+
+    env.set_time(START + 1).await;
+    for validator in &validators {
+        let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), 0);
+        env.send(&[ix], &[validator]).await.unwrap();
+    }
+    env.set_time(START + game.punch_in_length).await;
+    for validator in &validators {
+        let ix = TestEnv::reveal_ix(GAME, &validator.pubkey(), 0);
+        env.send(&[ix], &[validator]).await.unwrap();
+    }
+    env.set_time(START + game.punch_in_length + game.reveal_length).await;
+    validators
+}
+
+#[tokio::test]
+async fn commit_and_reveal_are_checked() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let validator = env.add_validator(GAME).await;
+    let game = env.game(GAME).await;
+
+    env.set_time(START + 1).await;
+    let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), 0);
+    env.send(&[ix], &[&validator]).await.unwrap();
+    let ix = TestEnv::punch_in_ix(GAME, &validator.pubkey(), 0);
+    assert_error(env.send(&[ix], &[&validator]).await, ErrorCode::AlreadyCommitted);
+
+    let ix = TestEnv::reveal_ix(GAME, &validator.pubkey(), 0);
+    assert_error(env.send(&[ix], &[&validator]).await, ErrorCode::NotInRevealPeriod);
+
+    env.set_time(START + game.punch_in_length).await;
+    let wrong_secret = TestEnv::reveal_ix(GAME, &validator.pubkey(), 1);
+    assert_error(env.send(&[wrong_secret], &[&validator]).await, ErrorCode::InvalidReveal);
+    let ix = TestEnv::reveal_ix(GAME, &validator.pubkey(), 0);
+    env.send(&[ix], &[&validator]).await.unwrap();
+    assert_eq!(env.game(GAME).await.reveal_count, 1);
+
+    // Not before the reveal window is over
+    assert_error(env.send(&[TestEnv::finalize_ix(GAME)], &[]).await, ErrorCode::NotInMintPeriod);
+}
+
+#[tokio::test]
+async fn first_finalize_after_reveals_fixes_the_seed_slot() {
+    let mut env = TestEnv::start().await;
+    revealed_game(&mut env).await;
+    assert_eq!(env.game(GAME).await.seed_slot, 0);
+
+    let slot = env.slot().await;
+    env.send(&[TestEnv::finalize_ix(GAME)], &[]).await.unwrap();
+    let game = env.game(GAME).await;
+    assert_eq!(game.seed_slot, slot + SEED_SLOT_DELAY);
+    assert_eq!(game.last_seed, None);
+
+    // Until the slot is produced
+    env.set_slot_hashes(&[game.seed_slot - 1]);
+    assert_error(env.send(&[TestEnv::finalize_ix(GAME)], &[]).await, ErrorCode::SeedSlotPending);
+
+    env.set_slot_hashes(&[game.seed_slot + 1, game.seed_slot]);
+    env.send(&[TestEnv::finalize_ix(GAME)], &[]).await.unwrap();
+    let game = env.game(GAME).await;
+    assert!(game.last_seed.is_some());
+    assert_error(env.send(&[TestEnv::finalize_ix(GAME)], &[]).await, ErrorCode::SeedAlreadyFinalized);
+}
+
+#[tokio::test]
+async fn an_aged_out_seed_slot_is_replaced() {
+    let mut env = TestEnv::start().await;
+    revealed_game(&mut env).await;
+    env.send(&[TestEnv::finalize_ix(GAME)], &[]).await.unwrap();
+    let expired = env.game(GAME).await.seed_slot;
+
+    env.ctx.warp_to_slot(expired + 1_000).unwrap();
+    let game = env.game(GAME).await;
+    env.set_time(START + game.punch_in_length + game.reveal_length + 1).await;
+    // The sysvar no longer reaches back to the seed slot
+    env.set_slot_hashes(&[expired + 999, expired + 998]);
+    env.send(&[TestEnv::finalize_ix(GAME)], &[]).await.unwrap();
+
+    let game = env.game(GAME).await;
+    assert_eq!(game.seed_slot, expired + 1_000 + SEED_SLOT_DELAY);
+    assert_eq!(game.last_seed, None);
+
+    env.set_slot_hashes(&[game.seed_slot]);
+    env.send(&[TestEnv::finalize_ix(GAME)], &[]).await.unwrap();
+    assert!(env.game(GAME).await.last_seed.is_some());
+}
@@ -0,0 +1,144 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, SlashEvidence};
+use solana_program_test::BanksClientError;
+use solana_sdk::{incinerator, instruction::AccountMeta, pubkey::Pubkey, signature::Keypair, signer::Signer};
+
+const GAME: u32 = 1;
+
+async fn slash(
+    env: &mut TestEnv,
+    game_number: u32,
+    validator: &Pubkey,
+    evidence: SlashEvidence,
+    games: &[u32],
+) -> Result<(), BanksClientError> {
+    let accounts = fancoin::accounts::SlashValidator {
+        validator_pda: validator_pda(game_number, validator),
+        game: game_pda(game_number),
+        reporter: env.owner.pubkey(),
+        incinerator: incinerator::ID,
+    };
+    let mut ix = instruction(accounts, fancoin::instruction::SlashValidator { evidence });
+    ix.accounts.extend(games.iter().map(|n| AccountMeta::new_readonly(game_pda(*n), false)));
+    env.send(&[ix], &[]).await
+}
+
+/// Game with bucketed groups of two, six validators and three players, past its seed.
+/// Returns the validators, a player and a validator outside that player's group.
+async fn grouped_game(env: &mut TestEnv) -> (Vec<Keypair>, Player, usize, Keypair) {
+    env.create_game(GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Bucketed { group_size: 2 }).await;
+    let mut validators = Vec::new();
+    for _ in 0..6 {
+        validators.push(env.add_validator(GAME).await);
+    }
+    let server = env.add_server(GAME).await;
+    let mut players = Vec::new();
+    for name in ["alice", "bob", "carol"] {
+        let player = env.register_player(name).await;
+        env.join_game(GAME, &player).await;
+        players.push(player);
+    }
+    let signers: Vec<&Keypair> = validators.iter().collect();
+    env.seed_round(0, &[(GAME, &signers)]).await;
+
+    let game = env.game(GAME).await;
+    for player in players {
+        if let Some(outsider) = validators.iter().position(|v| !in_player_group(&game, &v.pubkey(), &player)) {
+            return (validators, player, outsider, server);
+        }
+    }
+    panic!("every validator landed in every player's group");
+}
+
+#[tokio::test]
+async fn approvals_outside_the_players_group_are_struck_not_recorded() {
+    let mut env = TestEnv::start().await;
+    let (validators, player, outsider, server) = grouped_game(&mut env).await;
+    let outsider = &validators[outsider];
+
+    env.submit(GAME, 0, &server, outsider, &[&player]).await.unwrap();
+
+    let game = env.game(GAME).await;
+    assert!(game.minting_agreements.iter().all(|ma| ma.player_id != player.id));
+    assert_eq!(env.validator(GAME, &outsider.pubkey()).await.failed_approvals, 1);
+}
+
+#[tokio::test]
+async fn repeated_group_failures_are_slashable() {
+    let mut env = TestEnv::start().await;
+    let (validators, player, outsider, server) = grouped_game(&mut env).await;
+    let outsider = &validators[outsider];
+
+    assert_error(
+        slash(&mut env, GAME, &outsider.pubkey(), SlashEvidence::RepeatedGroupFailures, &[]).await,
+        ErrorCode::InvalidEvidence,
+    );
+
+    for _ in 0..fancoin::MAX_FAILED_APPROVALS {
+        env.submit(GAME, 0, &server, outsider, &[&player]).await.unwrap();
+    }
+    let stake_before = env.validator(GAME, &outsider.pubkey()).await.stake;
+    let total_before = env.game(GAME).await.total_stake;
+    slash(&mut env, GAME, &outsider.pubkey(), SlashEvidence::RepeatedGroupFailures, &[])
+        .await
+        .unwrap();
+
+    let slashed = stake_before * fancoin::SLASH_BPS / 10_000;
+    let val_pda = env.validator(GAME, &outsider.pubkey()).await;
+    assert_eq!(val_pda.stake, stake_before - slashed);
+    assert_eq!(val_pda.failed_approvals, 0);
+    assert_eq!(env.game(GAME).await.total_stake, total_before - slashed);
+}
+
+#[tokio::test]
+async fn conflicting_approvals_need_exclusive_games_including_the_validators() {
+    let mut env = TestEnv::start().await;
+    let validator = env.funded_keypair().await;
+    let alice = env.register_player("alice").await;
+    let mut servers = Vec::new();
+    for game_number in 1..=3 {
+        env.create_game(game_number).await;
+        env.set_grouping_strategy(game_number, GroupingStrategy::Ring { radius: 100 }).await;
+        env.register_validator(game_number, &validator, fancoin::MIN_VALIDATOR_STAKE).await;
+        env.join_game(game_number, &alice).await;
+        servers.push(env.add_server(game_number).await);
+    }
+    let signers = [&validator];
+    env.seed_round(0, &[(1, &signers), (2, &signers), (3, &signers)]).await;
+    env.submit(1, 0, &servers[0], &validator, &[&alice]).await.unwrap();
+    env.submit(2, 0, &servers[1], &validator, &[&alice]).await.unwrap();
+
+    let evidence = || SlashEvidence::ConflictingApprovals { player_id: alice.id };
+    // A player may play several games that don't claim exclusivity
+    assert_error(
+        slash(&mut env, 1, &validator.pubkey(), evidence(), &[1, 2]).await,
+        ErrorCode::InvalidEvidence,
+    );
+
+    for game_number in 1..=3 {
+        let accounts = env.update_game(game_number);
+        let args = fancoin::instruction::SetExclusivePlay { game_number, exclusive_play: true };
+        env.send(&[instruction(accounts, args)], &[]).await.unwrap();
+    }
+    // The stake slashed must be from one of the two games
+    assert_error(
+        slash(&mut env, 3, &validator.pubkey(), evidence(), &[1, 2]).await,
+        ErrorCode::InvalidEvidence,
+    );
+    // No approval for another player
+    assert_error(
+        slash(&mut env, 1, &validator.pubkey(), SlashEvidence::ConflictingApprovals { player_id: alice.id + 1 }, &[1, 2])
+            .await,
+        ErrorCode::InvalidEvidence,
+    );
+
+    slash(&mut env, 1, &validator.pubkey(), evidence(), &[1, 2]).await.unwrap();
+    assert!(env.validator(1, &validator.pubkey()).await.stake < fancoin::MIN_VALIDATOR_STAKE);
+    assert_error(
+        slash(&mut env, 1, &validator.pubkey(), evidence(), &[2, 1]).await,
+        ErrorCode::AlreadySlashed,
+    );
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::*;
+use fancoin::{ErrorCode, GroupingStrategy, ValidatorIndex};
+use solana_sdk::{instruction::AccountMeta, signer::Signer, system_program};
+
+const GAME: u32 = 1;
+const OTHER_GAME: u32 = 2;
+
+#[tokio::test]
+async fn validators_are_keyed_by_address_with_a_dense_index() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    let first = env.add_validator(GAME).await;
+    let second = env.add_validator(GAME).await;
+
+    for (index, validator) in [&first, &second].into_iter().enumerate() {
+        let entry: ValidatorIndex = env.account(&validator_index_pda(GAME, index as u32)).await;
+        assert_eq!(entry.validator, validator.pubkey());
+        let val_pda = env.validator(GAME, &validator.pubkey()).await;
+        assert_eq!((val_pda.game_number, val_pda.index), (GAME, index as u32));
+    }
+    assert_eq!(env.game(GAME).await.validator_count, 2);
+
+    // The address seeds the PDA, so a second registration collides with the first
+    let index = env.game(GAME).await.validator_count;
+    let accounts = fancoin::accounts::RegisterValidatorPda {
+        game: game_pda(GAME),
+        validator_pda: validator_pda(GAME, &first.pubkey()),
+        validator_index: validator_index_pda(GAME, index),
+        user: first.pubkey(),
+        system_program: system_program::ID,
+    };
+    let ix = instruction(accounts, fancoin::instruction::RegisterValidatorPda { game_number: GAME });
+    assert!(env.send(&[ix], &[&first]).await.is_err());
+}
+
+#[tokio::test]
+async fn minting_lists_need_the_signers_validator_pda_for_the_game() {
+    let mut env = TestEnv::start().await;
+    env.create_game(GAME).await;
+    env.create_game(OTHER_GAME).await;
+    env.set_grouping_strategy(GAME, GroupingStrategy::Ring { radius: 100 }).await;
+    let validator = env.add_validator(GAME).await;
+    let outsider = env.add_validator(OTHER_GAME).await;
+    let server = env.add_server(GAME).await;
+    let alice = env.register_player("alice").await;
+    env.join_game(GAME, &alice).await;
+    env.seed_round(0, &[(GAME, &[&validator])]).await;
+
+    // Registered in another game only; that game's PDA does not count here
+    let mut ixs = TestEnv::submit_ixs(GAME, 0, &server, &outsider.pubkey(), &[&alice]);
+    ixs[1].accounts[4] = AccountMeta::new(validator_pda(OTHER_GAME, &outsider.pubkey()), false);
+    assert_error(env.send(&ixs, &[&outsider]).await, ErrorCode::GameNumberMismatch);
+
+    // Without any ValidatorPda for the signer
+    let mut ixs = TestEnv::submit_ixs(GAME, 0, &server, &validator.pubkey(), &[&alice]);
+    ixs[1].accounts.remove(4);
+    assert_error(env.send(&ixs, &[&validator]).await, ErrorCode::ValidatorNotRegistered);
+
+    env.submit(GAME, 0, &server, &validator, &[&alice]).await.unwrap();
+    let game = env.game(GAME).await;
+    assert_eq!(game.minting_agreements[0].validators, vec![validator.pubkey()]);
+}
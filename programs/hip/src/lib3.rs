@@ -1,4 +1,8 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::{set_return_data, MAX_RETURN_DATA};
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+use anchor_lang::solana_program::{ed25519_program, incinerator, sysvar, sysvar::slot_hashes};
+use anchor_lang::system_program;
 use sha3::{Digest, Keccak256};
 use std::convert::TryInto;
 
@@ -7,6 +11,80 @@ use std::convert::TryInto;
 // ------------------------------------------------------------------
 declare_id!("HP9ucKGU9Sad7EaWjrGULC2ZSyYD1ScxVPh15QmdRmut");
 
+// ------------------------------------------------------------------
+// Constants
+// ------------------------------------------------------------------
+/// Lamports a validator must keep staked before its minting lists are accepted.
+pub const MIN_VALIDATOR_STAKE: u64 = 1_000_000_000;
+/// Share of the current stake taken by one slash, in basis points.
+pub const SLASH_BPS: u64 = 5_000;
+/// Share of the slashed amount paid to the reporter, in basis points. The rest is burned.
+pub const REPORTER_REWARD_BPS: u64 = 2_000;
+/// Group-distance failures a validator may accumulate before it becomes slashable.
+pub const MAX_FAILED_APPROVALS: u32 = 3;
+/// Two approvals of the same player conflict if they fall in the same window of this length.
+pub const SLASHING_EPOCH_SECONDS: i64 = 3600;
+/// Default epoch length for a new game.
+pub const DEFAULT_EPOCH_SECONDS: i64 = 3600;
+/// Default punch-in (commit) window at the start of each epoch.
+pub const DEFAULT_PUNCH_IN_SECONDS: i64 = 600;
+/// Default reveal window after punch-in; the rest of the epoch is the mint window.
+pub const DEFAULT_REVEAL_SECONDS: i64 = 300;
+/// Slots between the `finalize_seed` call that fixes the seed slot and the slot itself.
+pub const SEED_SLOT_DELAY: u64 = 2;
+/// Default idle time after which a validator can be evicted from a game.
+pub const DEFAULT_INACTIVITY_SECONDS: i64 = 7 * 24 * 3600;
+/// Default tokens minted to a player per resolved agreement.
+pub const DEFAULT_PLAYER_REWARD: u64 = 1_000_000_000;
+/// Default tokens shared by the approving validators per resolved agreement.
+pub const DEFAULT_VALIDATOR_REWARD_POOL: u64 = 3_236_000_000;
+/// Default wait between requesting and finalizing a reward-address change.
+pub const DEFAULT_REWARD_CHANGE_DELAY: i64 = 2 * 24 * 3600;
+/// Upper bound for `DApp.max_players_per_authority`; sizes `PlayerAuthorityIndex`.
+pub const MAX_PLAYERS_PER_AUTHORITY: usize = 8;
+/// Share of normal rewards paid out by a probationary game, in basis points.
+pub const PROBATIONARY_REWARD_BPS: u64 = 5_000;
+/// Longest reason string accepted for a status transition.
+pub const MAX_STATUS_REASON_LEN: usize = 64;
+/// Longest `Game.description`; matches the space reserved in `Game::LEN`.
+pub const MAX_GAME_DESCRIPTION_LEN: usize = 64;
+/// Longest player name; names are also the seed of their `PlayerName` claim.
+pub const MAX_PLAYER_NAME_LEN: usize = 32;
+/// Longest display name in `GameMetadata`.
+pub const MAX_GAME_NAME_LEN: usize = 32;
+/// Longest manifest URI in `GameMetadata`.
+pub const MAX_METADATA_URI_LEN: usize = 200;
+/// Most servers a game can register in `GameMetadata`.
+pub const MAX_GAME_SERVERS: usize = 8;
+/// Longest server endpoint (host:port or URL).
+pub const MAX_SERVER_ENDPOINT_LEN: usize = 64;
+/// Size of the DApp owner set.
+pub const MAX_DAPP_OWNERS: usize = 6;
+/// Slots a proposal stays open for approval and execution (about three days).
+pub const PROPOSAL_TTL_SLOTS: u64 = 648_000;
+/// Most validators per `get_validator_group_info` call; keeps the result within return data.
+pub const MAX_GROUP_QUERY_VALIDATORS: usize = 20;
+/// Bucket size of the `GroupingStrategy::Bucketed` strategy new games start with.
+pub const DEFAULT_GROUP_SIZE: u32 = 4;
+/// Default wait between an agreement resolving and its payout.
+pub const DEFAULT_CHALLENGE_WINDOW_SECONDS: i64 = 3600;
+/// Default number of epochs an unresolved agreement is kept before it can be pruned.
+pub const DEFAULT_AGREEMENT_TTL_EPOCHS: u64 = 2;
+/// Most open agreements a game holds; sizes `Game::LEN`.
+pub const MAX_MINTING_AGREEMENTS: usize = 12;
+/// Most approvals recorded on one agreement; later approvals are ignored.
+pub const MAX_AGREEMENT_VALIDATORS: usize = 8;
+/// Most payouts a game holds in their challenge window; sizes `Game::LEN`.
+pub const MAX_PENDING_PAYOUTS: usize = 8;
+/// Longest evidence string (a URI or hash of the off-chain evidence) in a dispute.
+pub const MAX_DISPUTE_EVIDENCE_LEN: usize = 128;
+/// Longest message a player may attach to a ban appeal.
+pub const MAX_APPEAL_MESSAGE_LEN: usize = 128;
+/// Most games in one `submit_minting_batch`.
+pub const MAX_BATCH_GAMES: usize = 8;
+/// Prefix of every message a game server signs in an attestation.
+pub const ATTESTATION_DOMAIN: &[u8] = b"fancoin-attestation";
+
 //
 // ------------------------------------------------------------------
 // [PROGRAM] fancoin
@@ -20,19 +98,192 @@ pub mod fancoin {
     // ------------------------------------------------------------------
 
     /// Initialize a DApp that tracks the global count of players across *all* games.
+    /// The initializer becomes the only owner, with a threshold of one.
     pub fn initialize_dapp(ctx: Context<InitializeDapp>) -> Result<()> {
         let dapp = &mut ctx.accounts.dapp;
-        dapp.owner = ctx.accounts.user.key();
+        dapp.owners = [Pubkey::default(); MAX_DAPP_OWNERS];
+        dapp.owners[0] = ctx.accounts.user.key();
+        dapp.owners_count = 1;
+        dapp.threshold = 1;
         dapp.global_player_count = 0;
+        dapp.max_players_per_authority = 1;
+        dapp.reward_change_delay = DEFAULT_REWARD_CHANGE_DELAY;
+        dapp.proposal_count = 0;
+        dapp.next_player_id = 0;
+        dapp.next_game_number = 0;
         Ok(())
     }
 
-    /// (Optional) Relinquish ownership of the DApp.
+    /// Set the timelock on player reward-address changes.
+    pub fn set_reward_change_delay(ctx: Context<UpdateDapp>, delay: i64) -> Result<()> {
+        let dapp = &mut ctx.accounts.dapp;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        apply_reward_change_delay(dapp, delay)
+    }
+
+    /// Set how many players a single authority may control (1..=MAX_PLAYERS_PER_AUTHORITY).
+    pub fn set_max_players_per_authority(ctx: Context<UpdateDapp>, max_players: u8) -> Result<()> {
+        let dapp = &mut ctx.accounts.dapp;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        apply_max_players_per_authority(dapp, max_players)
+    }
+
+    /// Leave the DApp owner set. Refused if fewer owners than the threshold would remain,
+    /// so the DApp can never end up without enough owners to act.
     pub fn relinquish_ownership(ctx: Context<RelinquishOwnership>) -> Result<()> {
         let dapp = &mut ctx.accounts.dapp;
-        require!(dapp.owner == ctx.accounts.signer.key(), ErrorCode::Unauthorized);
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.is_owner(&signer), ErrorCode::Unauthorized);
+        remove_dapp_owner(dapp, &signer)
+    }
+
+    /// Propose an owner action. The proposer's approval is counted right away.
+    /// The proposal can be approved and executed for `PROPOSAL_TTL_SLOTS`.
+    pub fn propose_action(ctx: Context<ProposeAction>, action: ProposalAction) -> Result<()> {
+        let dapp = &mut ctx.accounts.dapp;
+        let proposer = ctx.accounts.proposer.key();
+        require!(dapp.is_owner(&proposer), ErrorCode::Unauthorized);
+        action.check_lengths()?;
+        let clock = Clock::get()?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = dapp.proposal_count;
+        proposal.bump = ctx.bumps.proposal;
+        proposal.proposer = proposer;
+        proposal.action = action.clone();
+        proposal.approvals = vec![proposer];
+        proposal.created_at = clock.unix_timestamp;
+        proposal.expires_at_slot = clock.slot + PROPOSAL_TTL_SLOTS;
+        dapp.proposal_count += 1;
+
+        emit!(ProposalCreatedEvent { proposal_id: proposal.id, proposer, action });
+        Ok(())
+    }
+
+    /// Add the signer's approval to a proposal.
+    pub fn approve_proposal(ctx: Context<ApproveProposal>, proposal_id: u64) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let proposal = &mut ctx.accounts.proposal;
+        let owner = ctx.accounts.owner.key();
+        require!(dapp.is_owner(&owner), ErrorCode::Unauthorized);
+        require!(!proposal.approvals.contains(&owner), ErrorCode::AlreadyApproved);
+        require!(Clock::get()?.slot <= proposal.expires_at_slot, ErrorCode::ProposalExpired);
+
+        // Approvals of removed owners no longer count; dropping them keeps the list bounded
+        proposal.approvals.retain(|k| dapp.is_owner(k));
+        proposal.approvals.push(owner);
+
+        emit!(ProposalApprovedEvent {
+            proposal_id,
+            owner,
+            approvals: proposal.approvals.len() as u8,
+        });
+        Ok(())
+    }
+
+    /// Execute a proposal once enough current owners have approved it, before it expires.
+    /// Anyone may call this. Game actions need the target `Game` account, `CreateGame`
+    /// needs the new game's address as `new_game` (paid for by the executor, and
+    /// rejected for any other action), and rejecting a disputed payout also needs the
+    /// player's `PlayerGameState`.
+    /// The proposal's rent goes back to the proposer.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>, proposal_id: u64) -> Result<()> {
+        let dapp = &mut ctx.accounts.dapp;
+        let proposal = &ctx.accounts.proposal;
+        let approvals = proposal.approvals.iter().filter(|k| dapp.is_owner(k)).count();
+        require!(approvals >= dapp.threshold as usize, ErrorCode::ThresholdNotMet);
+        require!(Clock::get()?.slot <= proposal.expires_at_slot, ErrorCode::ProposalExpired);
+
+        let executor = ctx.accounts.executor.key();
+        let action = proposal.action.clone();
+        let game = &mut ctx.accounts.game;
+        let player_game_state = ctx.accounts.player_game_state.as_mut();
+        match action.clone() {
+            ProposalAction::CreateGame { game_number, description, creator } => {
+                let new_game = ctx.accounts.new_game.as_mut().ok_or(ErrorCode::MissingGameAccount)?;
+                apply_new_game(dapp, new_game, game_number, description, creator)?
+            }
+            ProposalAction::SetGameStatus { game_number, new_status, reason } => {
+                apply_game_status(proposal_game(game, game_number)?, new_status, reason, executor)?
+            }
+            ProposalAction::ConfigureGameEpochs {
+                game_number,
+                epoch_length,
+                punch_in_length,
+                reveal_length,
+            } => apply_epoch_config(
+                proposal_game(game, game_number)?,
+                epoch_length,
+                punch_in_length,
+                reveal_length,
+            )?,
+            ProposalAction::SetQuorumPolicy { game_number, quorum_policy } => {
+                apply_quorum_policy(proposal_game(game, game_number)?, quorum_policy)?
+            }
+            ProposalAction::SetGroupingStrategy { game_number, grouping_strategy } => {
+                apply_grouping_strategy(proposal_game(game, game_number)?, grouping_strategy)?
+            }
+            ProposalAction::SetRewardConfig { game_number, reward_config } => {
+                apply_reward_config(proposal_game(game, game_number)?, reward_config)?
+            }
+            ProposalAction::SetInactivityThreshold { game_number, inactivity_threshold } => {
+                apply_inactivity_threshold(proposal_game(game, game_number)?, inactivity_threshold)?
+            }
+            ProposalAction::RetireGame { game_number, reason } => {
+                apply_retirement(proposal_game(game, game_number)?, reason, executor)?
+            }
+            ProposalAction::SetChallengeWindow { game_number, challenge_window } => {
+                apply_challenge_window(proposal_game(game, game_number)?, challenge_window)?
+            }
+            ProposalAction::SetAgreementTtl { game_number, agreement_ttl_epochs } => {
+                apply_agreement_ttl(proposal_game(game, game_number)?, agreement_ttl_epochs)?
+            }
+            ProposalAction::ResolveDispute { game_number, player_id, epoch, ruling } => {
+                apply_dispute_ruling(
+                    proposal_game(game, game_number)?,
+                    player_game_state,
+                    player_id,
+                    epoch,
+                    ruling,
+                    executor,
+                )?
+            }
+            ProposalAction::SetRewardChangeDelay { delay } => apply_reward_change_delay(dapp, delay)?,
+            ProposalAction::SetMaxPlayersPerAuthority { max_players } => {
+                apply_max_players_per_authority(dapp, max_players)?
+            }
+            ProposalAction::AddOwner { owner } => add_dapp_owner(dapp, owner)?,
+            ProposalAction::RemoveOwner { owner } => remove_dapp_owner(dapp, &owner)?,
+            ProposalAction::SetThreshold { threshold } => {
+                require!(
+                    threshold >= 1 && threshold <= dapp.owners_count,
+                    ErrorCode::InvalidThreshold
+                );
+                dapp.threshold = threshold;
+            }
+            ProposalAction::SetExclusivePlay { game_number, exclusive_play } => {
+                apply_exclusive_play(proposal_game(game, game_number)?, exclusive_play)?
+            }
+        }
+
+        emit!(ProposalExecutedEvent { proposal_id, executor, action });
+        Ok(())
+    }
 
-        dapp.owner = Pubkey::default();
+    /// Withdraw a proposal before it is executed. The proposer may cancel at any time;
+    /// once the proposal has expired anyone may clear it. The rent goes back to the proposer.
+    pub fn cancel_proposal(ctx: Context<CancelProposal>, proposal_id: u64) -> Result<()> {
+        let proposal = &ctx.accounts.proposal;
+        let signer = ctx.accounts.signer.key();
+        let expired = Clock::get()?.slot > proposal.expires_at_slot;
+        require!(signer == proposal.proposer || expired, ErrorCode::Unauthorized);
+
+        emit!(ProposalCancelledEvent {
+            proposal_id,
+            proposer: proposal.proposer,
+            cancelled_by: signer,
+            expired,
+        });
         Ok(())
     }
 
@@ -42,75 +293,426 @@ pub mod fancoin {
 
     /// Initialize a minimal `Game` with basic fields (game_number, validator_count, etc.).
     /// We keep the old `minting_agreements` array as well, for legacy logic.
+    ///
+    /// Only a DApp owner acting alone may create a game; with a higher threshold, owners
+    /// use a `CreateGame` proposal. `creator` is recorded as the game's creator: it may
+    /// edit the game's description and metadata and earns `creator_share_bps`.
+    /// New games start on probation.
+    ///
+    /// Game numbers only go up: `game_number` must be at least `dapp.next_game_number`,
+    /// so a closed game's number is never handed out again.
     pub fn initialize_game(
         ctx: Context<InitializeGame>,
         game_number: u32,
         description: String,
+        creator: Pubkey,
+    ) -> Result<()> {
+        let dapp = &mut ctx.accounts.dapp;
+        require!(dapp.can_act_alone(&ctx.accounts.user.key()), ErrorCode::Unauthorized);
+        apply_new_game(dapp, &mut ctx.accounts.game, game_number, description, creator)
+    }
+
+    /// Move a game along the lifecycle. Only the transitions allowed by
+    /// `GameStatus::can_transition_to` are accepted, and each one is logged with a reason.
+    ///
+    /// A DApp owner acting alone (threshold of one) may make any allowed transition.
+    /// The game's creator may only move between unprivileged statuses, never into or
+    /// out of `Whitelisted` or `Blacklisted`. With a higher threshold, owners use a
+    /// `SetGameStatus` proposal.
+    pub fn update_game_status(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        new_status: GameStatus,
+        description: String,
+        reason: String,
     ) -> Result<()> {
         let dapp = &ctx.accounts.dapp;
-        // old check for ownership
-        require!(
-            dapp.owner == ctx.accounts.user.key() || dapp.owner == Pubkey::default(),
-            ErrorCode::Unauthorized
-        );
+        let game = &mut ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        let is_owner = dapp.can_act_alone(&signer);
+        require!(is_owner || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(description.len() <= MAX_GAME_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong);
+
+        // Whitelisting, blacklisting and reinstating stay with the DApp owners
+        if !is_owner {
+            require!(
+                !game.status.is_privileged() && !new_status.is_privileged(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        game.description = description;
+        apply_game_status(game, new_status, reason, signer)
+    }
 
+    /// Edit a game's description without touching its status.
+    /// Allowed for the game's creator and a DApp owner.
+    pub fn set_game_description(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        description: String,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
         let game = &mut ctx.accounts.game;
-        game.game_number = game_number;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(description.len() <= MAX_GAME_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong);
+
         game.description = description;
-        game.status = 0; // e.g. 0 = NotStarted
-        game.validator_count = 0;
-        game.last_seed = None;
-        game.last_punch_in_time = None;
-        // Legacy leftover array in the Game account
-        game.minting_agreements = Vec::new(); 
         Ok(())
     }
 
-    pub fn update_game_status(
+    /// Set the epoch length and the punch-in and reveal windows at the start of each epoch.
+    /// The change starts a fresh epoch right away, so the current seed is dropped.
+    pub fn configure_game_epochs(
         ctx: Context<UpdateGameStatus>,
         game_number: u32,
-        new_status: u8,
-        description: String
+        epoch_length: i64,
+        punch_in_length: i64,
+        reveal_length: i64,
     ) -> Result<()> {
         let dapp = &ctx.accounts.dapp;
         let game = &mut ctx.accounts.game;
-        require!(dapp.owner == ctx.accounts.signer.key(), ErrorCode::Unauthorized);
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
         require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_epoch_config(game, epoch_length, punch_in_length, reveal_length)
+    }
 
-        game.status = new_status;
-        game.description = description;
+    /// Choose how many approvals an agreement needs before it can resolve.
+    /// Every policy still requires at least two approvals in the same group.
+    pub fn set_quorum_policy(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        quorum_policy: QuorumPolicy,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_quorum_policy(game, quorum_policy)
+    }
+
+    /// Choose how validators are sorted into groups for the same-group check.
+    pub fn set_grouping_strategy(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        grouping_strategy: GroupingStrategy,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_grouping_strategy(game, grouping_strategy)
+    }
+
+    /// Tune the rewards paid per resolved agreement and the per-epoch emission cap.
+    pub fn set_reward_config(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        reward_config: RewardConfig,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_reward_config(game, reward_config)
+    }
+
+    /// Set how long a validator may stay idle before anyone can evict it.
+    pub fn set_inactivity_threshold(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        inactivity_threshold: i64,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_inactivity_threshold(game, inactivity_threshold)
+    }
+
+    /// Set how long resolved agreements wait, open to disputes, before they pay out.
+    /// Applies to agreements resolved from now on.
+    pub fn set_challenge_window(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        challenge_window: i64,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_challenge_window(game, challenge_window)
+    }
+
+    /// Set how many epochs an unresolved agreement is kept before `prune_expired_agreements`
+    /// may drop it.
+    pub fn set_agreement_ttl(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        agreement_ttl_epochs: u64,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_agreement_ttl(game, agreement_ttl_epochs)
+    }
+
+    /// Mark the game as exclusive: its players can't be active in another exclusive game
+    /// at the same time, which makes `ConflictingApprovals` evidence usable against it.
+    pub fn set_exclusive_play(
+        ctx: Context<UpdateGameStatus>,
+        game_number: u32,
+        exclusive_play: bool,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &mut ctx.accounts.game;
+        require!(dapp.can_act_alone(&ctx.accounts.signer.key()), ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_exclusive_play(game, exclusive_play)
+    }
+
+    /// Create or update a game's `GameMetadata`: a display name and the URI of its
+    /// off-chain JSON manifest. Allowed for the game's creator and a DApp owner.
+    pub fn set_game_metadata(
+        ctx: Context<SetGameMetadata>,
+        game_number: u32,
+        name: String,
+        uri: String,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(name.len() <= MAX_GAME_NAME_LEN, ErrorCode::NameTooLong);
+        require!(uri.len() <= MAX_METADATA_URI_LEN, ErrorCode::UriTooLong);
+
+        let metadata = &mut ctx.accounts.metadata;
+        metadata.game_number = game_number;
+        metadata.bump = ctx.bumps.metadata;
+        metadata.name = name;
+        metadata.uri = uri;
         Ok(())
     }
 
-    /// Punch in as a validator (placeholder logic).
-    pub fn punch_in(ctx: Context<PunchIn>, game_number: u32) -> Result<()> {
-        let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp;
+    /// Register a game server that validators should watch. Servers are identified
+    /// by their signing key; the endpoint is where validators connect.
+    pub fn add_game_server(
+        ctx: Context<UpdateGameServers>,
+        game_number: u32,
+        endpoint: String,
+        signing_key: Pubkey,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(endpoint.len() <= MAX_SERVER_ENDPOINT_LEN, ErrorCode::EndpointTooLong);
+
+        let metadata = &mut ctx.accounts.metadata;
+        require!(
+            !metadata.servers.iter().any(|s| s.signing_key == signing_key),
+            ErrorCode::ServerAlreadyRegistered
+        );
+        require!(metadata.servers.len() < MAX_GAME_SERVERS, ErrorCode::TooManyServers);
+        metadata.servers.push(GameServer { endpoint, signing_key });
+
+        emit!(GameServerRegisteredEvent { game_number, signing_key });
+        Ok(())
+    }
+
+    /// Remove a registered game server by its signing key.
+    pub fn remove_game_server(
+        ctx: Context<UpdateGameServers>,
+        game_number: u32,
+        signing_key: Pubkey,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        let metadata = &mut ctx.accounts.metadata;
+        let pos = metadata
+            .servers
+            .iter()
+            .position(|s| s.signing_key == signing_key)
+            .ok_or(ErrorCode::ServerNotFound)?;
+        metadata.servers.remove(pos);
+
+        emit!(GameServerRemovedEvent { game_number, signing_key });
+        Ok(())
+    }
 
+    /// Retire a game for good. Pending agreements are dropped, nothing more can be
+    /// minted, and validators may withdraw their stake with `withdraw_validator`.
+    /// Payouts already queued still go through `release_payouts` and disputes.
+    /// `reason` is recorded in `GameRetiredEvent`, like a status change's.
+    /// Allowed for the game's creator and a DApp owner, or through a `RetireGame` proposal.
+    pub fn retire_game(ctx: Context<UpdateGameStatus>, game_number: u32, reason: String) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
         let game = &mut ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_retirement(game, reason, signer)
+    }
+
+    /// Close a retired game once every validator has left and every queued payout is
+    /// settled. The rent of the `Game`
+    /// and, if it exists, its `GameMetadata` goes to the creator.
+    /// Allowed for the game's creator and a DApp owner.
+    ///
+    /// `PlayerGameState`s and `PlayerBan`s of the game stay behind, but the game number
+    /// is never reused, so they can't carry over to a new game.
+    pub fn close_game(ctx: Context<CloseGame>, game_number: u32) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
         require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
-        require!(game.status != 2, ErrorCode::GameIsBlacklisted);
-
-        // Example random seed logic
-        let validator_key = ctx.accounts.validator.key();
-        let mut hasher = Keccak256::new();
-        hasher.update(validator_key.to_bytes());
-        hasher.update(clock.slot.to_le_bytes());
-        let hash_res = hasher.finalize();
-        let seed = u64::from_le_bytes(
-            hash_res[0..8].try_into().map_err(|_| ErrorCode::HashConversionError)?
+        require!(game.status == GameStatus::Retired, ErrorCode::GameNotRetired);
+        require!(
+            game.validator_count == 0 && game.total_stake == 0,
+            ErrorCode::GameHasValidators
         );
+        require!(game.pending_payouts.is_empty(), ErrorCode::PayoutsPending);
+
+        // Same steps as Anchor's `close`, done by hand since the metadata is optional
+        let metadata_info = ctx.accounts.metadata.to_account_info();
+        if metadata_info.owner == ctx.program_id {
+            let rent = metadata_info.lamports();
+            metadata_info.sub_lamports(rent)?;
+            ctx.accounts.creator.add_lamports(rent)?;
+            metadata_info.assign(&system_program::ID);
+            metadata_info.realloc(0, false)?;
+        }
+        // the `close` constraint hands the game's rent to the creator
+        Ok(())
+    }
+
+    /// Punch in as a validator during the punch-in window of the current epoch,
+    /// committing to a secret with `commitment = keccak(secret || validator)`.
+    /// The secret is revealed in `reveal_seed`; nobody can steer the seed by
+    /// punching in repeatedly, since the commitments hide the secrets.
+    pub fn punch_in(ctx: Context<PunchIn>, game_number: u32, commitment: [u8; 32]) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(game.status != GameStatus::Blacklisted, ErrorCode::GameIsBlacklisted);
+        require!(game.status != GameStatus::Retired, ErrorCode::GameIsRetired);
+
+        let (epoch, offset) = game.epoch_at(current_time)?;
+        require!(offset < game.punch_in_length, ErrorCode::NotInPunchInPeriod);
+
+        // First punch-in of the epoch opens a new commit-reveal round
+        if game.seed_epoch != Some(epoch) {
+            game.seed_epoch = Some(epoch);
+            game.last_seed = None;
+            game.seed_accumulator = [0u8; 32];
+            game.commit_count = 0;
+            game.reveal_count = 0;
+            game.seed_slot = 0;
+        }
 
-        game.last_seed = Some(seed);
+        let val_pda = &mut ctx.accounts.validator_pda;
+        require!(val_pda.commitment_epoch != Some(epoch), ErrorCode::AlreadyCommitted);
+        val_pda.seed_commitment = Some(commitment);
+        val_pda.commitment_epoch = Some(epoch);
+        val_pda.last_activity = current_time;
+
+        game.commit_count += 1;
         game.last_punch_in_time = Some(current_time);
         Ok(())
     }
 
+    /// Reveal the secret behind this epoch's commitment during the reveal window.
+    /// `finalize_seed` turns the revealed secrets into the seed once the window is over.
+    pub fn reveal_seed(ctx: Context<RevealSeed>, game_number: u32, secret: [u8; 32]) -> Result<()> {
+        let current_time = Clock::get()?.unix_timestamp;
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        let (epoch, offset) = game.epoch_at(current_time)?;
+        require!(
+            offset >= game.punch_in_length && offset < game.punch_in_length + game.reveal_length,
+            ErrorCode::NotInRevealPeriod
+        );
+
+        let val_pda = &mut ctx.accounts.validator_pda;
+        require!(val_pda.commitment_epoch == Some(epoch), ErrorCode::InvalidReveal);
+        let commitment = val_pda.seed_commitment.ok_or(ErrorCode::InvalidReveal)?;
+        require!(
+            seed_commitment(&secret, &val_pda.address) == commitment,
+            ErrorCode::InvalidReveal
+        );
+        val_pda.seed_commitment = None;
+
+        for (acc, byte) in game.seed_accumulator.iter_mut().zip(secret.iter()) {
+            *acc ^= byte;
+        }
+        game.reveal_count += 1;
+        Ok(())
+    }
+
+    /// Permissionless crank: once the reveal window is over, finalize the seed
+    /// from whatever was revealed, even if some validators never revealed.
+    ///
+    /// The secrets are mixed with the hash of `game.seed_slot`. The first call after
+    /// the reveal window fixes that slot `SEED_SLOT_DELAY` slots ahead, once every
+    /// secret is in, so no revealer can know its hash. Later calls fail with
+    /// `SeedSlotPending` until the slot has passed, then finalize. If the slot has aged
+    /// out of SlotHashes by then, the call fixes a new one instead.
+    pub fn finalize_seed(ctx: Context<FinalizeSeed>, game_number: u32) -> Result<()> {
+        let clock = Clock::get()?;
+        let current_time = clock.unix_timestamp;
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        let (epoch, offset) = game.epoch_at(current_time)?;
+        require!(
+            offset >= game.punch_in_length + game.reveal_length,
+            ErrorCode::NotInMintPeriod
+        );
+        require!(game.seed_epoch == Some(epoch), ErrorCode::NoSeed);
+        require!(game.last_seed.is_none(), ErrorCode::SeedAlreadyFinalized);
+
+        if game.seed_slot != 0 {
+            if let Some(slot_hash) = slot_hash_at(&ctx.accounts.slot_hashes.to_account_info(), game.seed_slot)? {
+                return finalize_epoch_seed(game, epoch, &slot_hash);
+            }
+        }
+
+        let expired_slot = (game.seed_slot != 0).then_some(game.seed_slot);
+        game.seed_slot = clock.slot + SEED_SLOT_DELAY;
+        emit!(SeedSlotFixedEvent {
+            game_number,
+            epoch,
+            seed_slot: game.seed_slot,
+            expired_slot,
+        });
+        Ok(())
+    }
+
     // ------------------------------------------------------------------
     //  3) Player + Validator PDAs
     // ------------------------------------------------------------------
 
     /// Create (register) a brand-new Player PDA. Increments the dapp.global_player_count.
+    ///
+    /// Also records the new index in the authority's `PlayerAuthorityIndex`, which caps
+    /// how many players one authority may hold (`dapp.max_players_per_authority`). The
+    /// authority signs, so nobody else can use up its slots.
+    ///
+    /// Names are unique: the name is claimed in a `PlayerName` account, and registering
+    /// a name that is already claimed fails with `PlayerNameExists`.
     /// 
     /// You could store a "per_player_approvals" vector here, for dynamic validator approvals
     /// or minted states. If so, remember to size the `PlayerPda::LEN` accordingly (or do 
@@ -118,47 +720,274 @@ pub mod fancoin {
     pub fn register_player_pda(
         ctx: Context<RegisterPlayerPda>,
         name: String,
-        authority_address: Pubkey,
         reward_address: Pubkey,
     ) -> Result<()> {
         let dapp = &mut ctx.accounts.dapp;
         let player = &mut ctx.accounts.player_pda;
+        let authority_address = ctx.accounts.authority.key();
+        require!(name.len() <= MAX_PLAYER_NAME_LEN, ErrorCode::NameTooLong);
+
+        // A fresh claim is zeroed; a taken one holds its canonical bump, never 0 in practice
+        let name_claim = &mut ctx.accounts.player_name;
+        require!(name_claim.bump == 0, ErrorCode::PlayerNameExists);
+        name_claim.player_id = dapp.next_player_id;
+        name_claim.bump = ctx.bumps.player_name;
+
+        let authority_index = &mut ctx.accounts.authority_index;
+        authority_index.authority = authority_address;
+        require!(
+            authority_index.player_indices.len() < dapp.max_players_per_authority as usize,
+            ErrorCode::TooManyPlayersForAuthority
+        );
+        authority_index.player_indices.push(dapp.global_player_count);
     
         // Basic fields
+        player.index = dapp.global_player_count;
+        player.bump = ctx.bumps.player_pda;
+        player.id = dapp.next_player_id;
         player.name = name;
         player.authority = authority_address;
         player.reward_address = reward_address;
         player.last_name_change = None;
         player.last_reward_change = None;
+        player.pending_reward_address = None;
+        player.pending_reward_requested_at = None;
+        player.game_state_count = 0;
 
         // If you store dynamic approvals, you'd do:
         // player.per_player_approvals = Vec::new(); 
         // or keep it as an Option, etc.
 
         dapp.global_player_count += 1;
+        dapp.next_player_id += 1;
+        Ok(())
+    }
+
+    /// Remove a player, as its authority. Player indexes stay dense, so pages have no
+    /// gaps: the player at the last index moves into the vacated slot and the last
+    /// `PlayerPda` is closed, its rent going to the authority. Pass that player as
+    /// `last_player_pda` unless the removed player is the last one, and its
+    /// `PlayerAuthorityIndex` as `moved_authority_index` when it has another authority.
+    ///
+    /// Per-game state is keyed by `PlayerPda.id`, so it follows the moved player.
+    /// The removed player's own `PlayerGameState`s must be closed with
+    /// `close_player_game_state` first. The removed player's name is released.
+    pub fn deregister_player(ctx: Context<DeregisterPlayer>, player_index: u32) -> Result<()> {
+        require!(ctx.accounts.player_pda.game_state_count == 0, ErrorCode::PlayerHasGameStates);
+        let authority = ctx.accounts.authority.key();
+        let last = ctx
+            .accounts
+            .dapp
+            .global_player_count
+            .checked_sub(1)
+            .ok_or(ErrorCode::NoPlayersRegistered)?;
+
+        let authority_index = &mut ctx.accounts.authority_index;
+        let pos = authority_index
+            .player_indices
+            .iter()
+            .position(|i| *i == player_index)
+            .ok_or(ErrorCode::PlayerNotInAuthorityIndex)?;
+        authority_index.player_indices.swap_remove(pos);
+
+        let player_id = ctx.accounts.player_pda.id;
+        let moved_from = if player_index == last {
+            ctx.accounts.player_pda.close(ctx.accounts.authority.to_account_info())?;
+            None
+        } else {
+            let moved = ctx
+                .accounts
+                .last_player_pda
+                .as_mut()
+                .ok_or(ErrorCode::MissingPlayerAccounts)?;
+
+            // The moved player's reverse index now points at the vacated slot
+            let moved_index = if moved.authority == authority {
+                &mut ctx.accounts.authority_index
+            } else {
+                let moved_index = ctx
+                    .accounts
+                    .moved_authority_index
+                    .as_mut()
+                    .ok_or(ErrorCode::MissingPlayerAccounts)?;
+                require_keys_eq!(moved_index.authority, moved.authority, ErrorCode::MissingPlayerAccounts);
+                moved_index
+            };
+            let slot = moved_index
+                .player_indices
+                .iter_mut()
+                .find(|i| **i == last)
+                .ok_or(ErrorCode::PlayerNotInAuthorityIndex)?;
+            *slot = player_index;
+
+            // Same steps as `delete_owner_logic`: copy the last entry over the removed one.
+            // The vacated account keeps its own index and bump, which its address depends on.
+            let vacated = &mut ctx.accounts.player_pda;
+            vacated.id = moved.id;
+            vacated.name = moved.name.clone();
+            vacated.authority = moved.authority;
+            vacated.reward_address = moved.reward_address;
+            vacated.last_name_change = moved.last_name_change;
+            vacated.last_reward_change = moved.last_reward_change;
+            vacated.pending_reward_address = moved.pending_reward_address;
+            vacated.pending_reward_requested_at = moved.pending_reward_requested_at;
+            vacated.game_state_count = moved.game_state_count;
+            moved.close(ctx.accounts.authority.to_account_info())?;
+            Some(last)
+        };
+
+        if ctx.accounts.authority_index.player_indices.is_empty() {
+            ctx.accounts
+                .authority_index
+                .close(ctx.accounts.authority.to_account_info())?;
+        }
+        ctx.accounts.dapp.global_player_count = last;
+
+        emit!(PlayerDeregisteredEvent { player_index, player_id, authority, moved_from });
+        Ok(())
+    }
+
+    /// Create the per-(game, player) state that tracks minting history.
+    /// A player can only be minted for in a game once this exists. It is keyed by the
+    /// player's `id`, so it stays with the player if its index changes.
+    pub fn init_player_game_state(
+        ctx: Context<InitPlayerGameState>,
+        game_number: u32,
+        player_index: u32,
+    ) -> Result<()> {
+        require!(ctx.accounts.game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        let state = &mut ctx.accounts.player_game_state;
+        state.game_number = game_number;
+        state.player_id = ctx.accounts.player_pda.id;
+        state.bump = ctx.bumps.player_game_state;
+        state.last_minted_epoch = None;
+        state.total_minted = 0;
+        state.lifetime_approvals = 0;
+        state.banned_until = None;
+        state.payer = ctx.accounts.user.key();
+        ctx.accounts.player_pda.game_state_count += 1;
+
+        emit!(PlayerJoinedGameEvent { game_number, player_index, player_id: state.player_id });
+        Ok(())
+    }
+
+    /// Close a player's state in one game, as its authority, refunding the rent to
+    /// whoever created it. The player's open agreements in the game are dropped; it
+    /// can't leave with a payout still queued or while banned. Once the game is closed
+    /// neither applies, and a ban left behind is closed too, its rent going back to
+    /// `banned_by`. A lapsed ban is closed the same way.
+    pub fn close_player_game_state(
+        ctx: Context<ClosePlayerGameState>,
+        game_number: u32,
+        player_index: u32,
+    ) -> Result<()> {
+        let player_id = ctx.accounts.player_pda.id;
+        let game_info = ctx.accounts.game.to_account_info();
+        let game_open = game_info.owner == ctx.program_id;
+
+        if let Some(until) = ctx.accounts.player_game_state.banned_until {
+            let now = Clock::get()?.unix_timestamp;
+            require!(!game_open || now >= until, ErrorCode::PlayerBanned);
+            let ban = ctx.accounts.ban.as_ref().ok_or(ErrorCode::MissingBanAccounts)?;
+            let refund = ctx.accounts.ban_refund.as_ref().ok_or(ErrorCode::MissingBanAccounts)?;
+            require_keys_eq!(refund.key(), ban.banned_by, ErrorCode::MissingBanAccounts);
+            ban.close(refund.to_account_info())?;
+        }
+
+        if game_open {
+            let mut game = Game::try_deserialize(&mut &game_info.try_borrow_data()?[..])?;
+            require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+            require!(
+                !game.pending_payouts.iter().any(|p| p.player_id == player_id),
+                ErrorCode::PlayerHasPendingPayout
+            );
+            game.minting_agreements.retain(|a| a.player_id != player_id);
+            game.try_serialize(&mut &mut game_info.try_borrow_mut_data()?[..])?;
+        }
+
+        let player_pda = &mut ctx.accounts.player_pda;
+        player_pda.game_state_count = player_pda.game_state_count.saturating_sub(1);
+
+        emit!(PlayerLeftGameEvent { game_number, player_index, player_id });
         Ok(())
     }
 
     /// Create a brand-new validator for a given Game.
+    ///
+    /// The `ValidatorPda` is keyed by (game_number, validator address), so a validator can
+    /// only register once per game. A `ValidatorIndex` keyed by (game_number, index) points
+    /// back to it, so pagination can still walk validators by dense index.
     pub fn register_validator_pda(
         ctx: Context<RegisterValidatorPda>,
         game_number: u32,
     ) -> Result<()> {
         let game = &mut ctx.accounts.game;
         require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(game.status != GameStatus::Retired, ErrorCode::GameIsRetired);
+
+        let index_entry = &mut ctx.accounts.validator_index;
+        index_entry.validator = ctx.accounts.user.key();
+        index_entry.bump = ctx.bumps.validator_index;
 
         let val_pda = &mut ctx.accounts.validator_pda;
+        val_pda.game_number = game_number;
+        val_pda.index = game.validator_count;
+        val_pda.bump = ctx.bumps.validator_pda;
         val_pda.address = ctx.accounts.user.key();
         val_pda.last_activity = Clock::get()?.unix_timestamp;
+        val_pda.stake = 0;
+        val_pda.failed_approvals = 0;
+        val_pda.last_conflict_slash_epoch = None;
+        val_pda.seed_commitment = None;
+        val_pda.commitment_epoch = None;
 
         // increment
         game.validator_count += 1;
         Ok(())
     }
 
-    /// Pagination helper for listing Player PDAs
-    pub fn get_player_list_pda_page(
-        ctx: Context<GetPlayerListPdaPage>,
+    /// Move lamports from the validator into its PDA as slashable stake.
+    pub fn stake_validator(
+        ctx: Context<StakeValidator>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InsufficientStake);
+        require!(ctx.accounts.game.status != GameStatus::Retired, ErrorCode::GameIsRetired);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.user.to_account_info(),
+                    to: ctx.accounts.validator_pda.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        let game = &mut ctx.accounts.game;
+        game.total_stake = game.total_stake.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        let val_pda = &mut ctx.accounts.validator_pda;
+        val_pda.stake = val_pda.stake.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(ValidatorStakedEvent {
+            game_number: val_pda.game_number,
+            validator: val_pda.address,
+            amount,
+            stake: val_pda.stake,
+            total_stake: game.total_stake,
+        });
+        Ok(())
+    }
+
+    /// Pagination helper for listing Player PDAs.
+    /// remaining_accounts are the `PlayerPda`s for `start_index..end_index`.
+    /// The page is written as a `PdaPage` via `set_return_data`; it stops early
+    /// if the next entry would not fit, and `next_index` says where to resume.
+    pub fn get_player_list_pda_page<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetPlayerListPdaPage<'info>>,
         start_index: u32,
         end_index: u32,
     ) -> Result<()> {
@@ -170,27 +999,122 @@ pub mod fancoin {
         let needed = (clamped_end - start_index) as usize;
         require!(ctx.remaining_accounts.len() >= needed, ErrorCode::InvalidRange);
 
+        let mut page = PdaPage::new();
         for (offset, acc_info) in ctx.remaining_accounts.iter().enumerate() {
             let i = start_index + offset as u32;
             if i >= clamped_end {
                 break;
             }
-            let maybe_player_pda = Account::<PlayerPda>::try_from(acc_info);
-            if let Ok(player_pda) = maybe_player_pda {
-                msg!("PlayerPda #{} => name: {}", i, player_pda.name);
-            } else {
-                msg!("PlayerPda #{} => <cannot load PDA>", i);
+            let player_pda = Account::<PlayerPda>::try_from(acc_info)?;
+            verify_player_pda(acc_info.key, &player_pda, i)?;
+
+            let entry = PdaPageEntry {
+                index: i,
+                key: acc_info.key(),
+                name: player_pda.name.clone(),
+                reward_address: player_pda.reward_address,
+                last_activity: None,
+            };
+            if !page.try_push(entry) {
+                break;
             }
         }
+        page.finish(start_index, clamped_end, dapp.global_player_count);
+
+        set_return_data(&page.try_to_vec()?);
         Ok(())
     }
 
-    /// Pagination helper for listing Validator PDAs
-    pub fn get_validator_list_pda_page(
-        ctx: Context<GetValidatorListPdaPage>,
+    /// Permissionless crank: remove a validator idle for longer than the game's
+    /// `inactivity_threshold`. Its stake goes back to the validator and the rent
+    /// goes to the cranker.
+    ///
+    /// Validator indexes stay dense: the last `ValidatorIndex` is moved into the
+    /// vacated slot and closed. Pass `last_validator_index` and `moved_validator_pda`
+    /// unless the evicted validator already holds the last index.
+    pub fn evict_inactive_validator(
+        ctx: Context<EvictInactiveValidator>,
         game_number: u32,
-        start_index: u32,
-        end_index: u32,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        let val_pda = &ctx.accounts.validator_pda;
+        require!(
+            now.saturating_sub(val_pda.last_activity) > game.inactivity_threshold,
+            ErrorCode::ValidatorStillActive
+        );
+
+        let evicted_index = val_pda.index;
+        let evicted = val_pda.address;
+        let stake = val_pda.stake;
+        vacate_validator_slot(
+            game,
+            evicted_index,
+            stake,
+            &mut ctx.accounts.validator_index,
+            ctx.accounts.last_validator_index.as_ref(),
+            ctx.accounts.moved_validator_pda.as_mut(),
+            ctx.accounts.cranker.to_account_info(),
+        )?;
+
+        // Stake goes home; the `close` constraint sends the rent to the cranker.
+        ctx.accounts.validator_pda.stake = 0;
+        ctx.accounts.validator_pda.sub_lamports(stake)?;
+        ctx.accounts.validator_wallet.add_lamports(stake)?;
+
+        emit!(ValidatorEvictedEvent {
+            game_number,
+            validator: evicted,
+            index: evicted_index,
+            cranker: ctx.accounts.cranker.key(),
+            refunded_stake: stake,
+            validator_count: game.validator_count,
+        });
+        Ok(())
+    }
+
+    /// Leave a retired game: the validator gets back its stake and the rent of its
+    /// `ValidatorPda` and index entry. Same swap-last bookkeeping as eviction.
+    pub fn withdraw_validator(ctx: Context<WithdrawValidator>, game_number: u32) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(game.status == GameStatus::Retired, ErrorCode::GameNotRetired);
+
+        let val_pda = &ctx.accounts.validator_pda;
+        let index = val_pda.index;
+        let stake = val_pda.stake;
+        vacate_validator_slot(
+            game,
+            index,
+            stake,
+            &mut ctx.accounts.validator_index,
+            ctx.accounts.last_validator_index.as_ref(),
+            ctx.accounts.moved_validator_pda.as_mut(),
+            ctx.accounts.validator.to_account_info(),
+        )?;
+
+        // the `close` constraint returns stake and rent together
+        emit!(ValidatorWithdrawnEvent {
+            game_number,
+            validator: ctx.accounts.validator.key(),
+            index,
+            withdrawn_stake: stake,
+            validator_count: game.validator_count,
+        });
+        Ok(())
+    }
+
+    /// Pagination helper for listing Validator PDAs.
+    /// remaining_accounts are the `ValidatorPda`s holding indexes `start_index..end_index`
+    /// (look them up through `ValidatorIndex`). Returns a `PdaPage` like
+    /// `get_player_list_pda_page`, with the validator address as `reward_address`.
+    pub fn get_validator_list_pda_page<'info>(
+        ctx: Context<'_, '_, 'info, 'info, GetValidatorListPdaPage<'info>>,
+        game_number: u32,
+        start_index: u32,
+        end_index: u32,
     ) -> Result<()> {
         let game = &ctx.accounts.game;
         require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
@@ -202,24 +1126,70 @@ pub mod fancoin {
         let needed = (clamped_end - start_index) as usize;
         require!(ctx.remaining_accounts.len() >= needed, ErrorCode::InvalidRange);
 
+        let mut page = PdaPage::new();
         for (offset, acc_info) in ctx.remaining_accounts.iter().enumerate() {
             let i = start_index + offset as u32;
             if i >= clamped_end {
                 break;
             }
-            let maybe_val_pda = Account::<ValidatorPda>::try_from(acc_info);
-            if let Ok(val_pda) = maybe_val_pda {
-                msg!("ValidatorPda #{} => address: {}", i, val_pda.address);
-            } else {
-                msg!("ValidatorPda #{} => <cannot load PDA>", i);
+            let val_pda = Account::<ValidatorPda>::try_from(acc_info)?;
+            verify_validator_pda(acc_info.key, &val_pda, game_number)?;
+            require!(val_pda.index == i, ErrorCode::InvalidRange);
+
+            let entry = PdaPageEntry {
+                index: i,
+                key: acc_info.key(),
+                name: String::new(),
+                reward_address: val_pda.address,
+                last_activity: Some(val_pda.last_activity),
+            };
+            if !page.try_push(entry) {
+                break;
             }
         }
+        page.finish(start_index, clamped_end, game.validator_count);
+
+        set_return_data(&page.try_to_vec()?);
+        Ok(())
+    }
+
+    /// Report the group of each validator in `validators` under the game's current seed,
+    /// the failover tolerance and which pairs would count as the same group in
+    /// `submit_minting_list`. Returned as a `GroupAssignment` via `set_return_data`,
+    /// and emitted as one `ValidatorInfoEvent` per validator.
+    ///
+    /// `group_assignment` computes the same result off-chain.
+    pub fn get_validator_group_info(
+        ctx: Context<GetValidatorGroupInfo>,
+        game_number: u32,
+        validators: Vec<Pubkey>,
+    ) -> Result<()> {
+        let game = &ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(validators.len() <= MAX_GROUP_QUERY_VALIDATORS, ErrorCode::InvalidRange);
+        let seed = game.last_seed.ok_or(ErrorCode::NoSeed)?;
+
+        let assignment =
+            group_assignment(&validators, seed, game.validator_count, game.grouping_strategy)?;
+        for (validator, group_id) in validators.iter().zip(assignment.group_ids.iter()) {
+            emit!(ValidatorInfoEvent {
+                game_number,
+                validator: *validator,
+                seed,
+                group_id: *group_id,
+                failover_tolerance: assignment.failover_tolerance,
+            });
+        }
+
+        set_return_data(&assignment.try_to_vec()?);
         Ok(())
     }
 
     // ------------------------------------------------------------------
     //  4) Name/Reward cooldown
     // ------------------------------------------------------------------
+    /// Rename a player, at most once a week. The old name's `PlayerName` claim is
+    /// closed and the new name is claimed, so it must not be taken.
     pub fn update_player_name_cooldown(
         ctx: Context<UpdatePlayerNameCooldown>,
         new_name: String
@@ -229,30 +1199,135 @@ pub mod fancoin {
         let one_week = 7 * 24 * 3600;
 
         require!(pda.authority == ctx.accounts.user.key(), ErrorCode::Unauthorized);
+        require!(new_name.len() <= MAX_PLAYER_NAME_LEN, ErrorCode::NameTooLong);
+        require!(new_name != pda.name, ErrorCode::PlayerNameExists);
 
         if let Some(last_change) = pda.last_name_change {
             require!(now - last_change >= one_week, ErrorCode::NameChangeCooldown);
         }
+
+        let name_claim = &mut ctx.accounts.new_player_name;
+        require!(name_claim.bump == 0, ErrorCode::PlayerNameExists);
+        name_claim.player_id = pda.id;
+        name_claim.bump = ctx.bumps.new_player_name;
+
         pda.name = new_name;
         pda.last_name_change = Some(now);
         Ok(())
     }
 
-    pub fn update_player_reward_cooldown(
-        ctx: Context<UpdatePlayerRewardCooldown>,
+    /// Step 1 of a reward-address change: queue `new_reward`. It only takes effect after
+    /// `dapp.reward_change_delay`, and the authority can cancel it until then.
+    /// The one-week cooldown between completed changes still applies.
+    pub fn request_reward_address_change(
+        ctx: Context<ChangePlayerReward>,
         new_reward: Pubkey
     ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
         let pda = &mut ctx.accounts.player_pda;
         let now = Clock::get()?.unix_timestamp;
         let one_week = 7 * 24 * 3600;
 
         require!(pda.authority == ctx.accounts.user.key(), ErrorCode::Unauthorized);
+        require!(pda.pending_reward_address.is_none(), ErrorCode::RewardChangePending);
 
         if let Some(last_change) = pda.last_reward_change {
-            require!(now - last_change >= one_week, ErrorCode::NameChangeCooldown);
+            require!(now - last_change >= one_week, ErrorCode::RewardChangeCooldown);
         }
+        pda.pending_reward_address = Some(new_reward);
+        pda.pending_reward_requested_at = Some(now);
+
+        emit!(RewardAddressChangeRequestedEvent {
+            player_index: pda.index,
+            current_reward_address: pda.reward_address,
+            pending_reward_address: new_reward,
+            executable_at: now + dapp.reward_change_delay,
+        });
+        Ok(())
+    }
+
+    /// Drop a queued reward-address change.
+    pub fn cancel_reward_address_change(ctx: Context<ChangePlayerReward>) -> Result<()> {
+        let pda = &mut ctx.accounts.player_pda;
+        require!(pda.authority == ctx.accounts.user.key(), ErrorCode::Unauthorized);
+        let cancelled = pda.pending_reward_address.ok_or(ErrorCode::NoRewardChangePending)?;
+
+        pda.pending_reward_address = None;
+        pda.pending_reward_requested_at = None;
+
+        emit!(RewardAddressChangeCancelledEvent {
+            player_index: pda.index,
+            cancelled_reward_address: cancelled,
+        });
+        Ok(())
+    }
+
+    /// Step 2: apply the queued change once the delay is over. Anyone may crank this,
+    /// since the outcome was fixed by the authority's request.
+    pub fn finalize_reward_address_change(ctx: Context<FinalizePlayerReward>) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let pda = &mut ctx.accounts.player_pda;
+        let now = Clock::get()?.unix_timestamp;
+
+        let new_reward = pda.pending_reward_address.ok_or(ErrorCode::NoRewardChangePending)?;
+        let requested_at = pda.pending_reward_requested_at.ok_or(ErrorCode::NoRewardChangePending)?;
+        require!(now - requested_at >= dapp.reward_change_delay, ErrorCode::RewardChangeTimelocked);
+
+        let old_reward = pda.reward_address;
         pda.reward_address = new_reward;
         pda.last_reward_change = Some(now);
+        pda.pending_reward_address = None;
+        pda.pending_reward_requested_at = None;
+
+        emit!(RewardAddressChangeFinalizedEvent {
+            player_index: pda.index,
+            old_reward_address: old_reward,
+            new_reward_address: new_reward,
+        });
+        Ok(())
+    }
+
+    /// Hand a player over to a new authority, moving its entry between reverse indexes.
+    /// The old index is closed to the current authority once it is empty.
+    pub fn update_player_authority(
+        ctx: Context<UpdatePlayerAuthority>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let pda = &mut ctx.accounts.player_pda;
+        require!(pda.authority == ctx.accounts.user.key(), ErrorCode::Unauthorized);
+        require!(new_authority != pda.authority, ErrorCode::Unauthorized);
+
+        let old_index = &mut ctx.accounts.old_authority_index;
+        let pos = old_index
+            .player_indices
+            .iter()
+            .position(|i| *i == pda.index)
+            .ok_or(ErrorCode::InvalidSeeds)?;
+        old_index.player_indices.swap_remove(pos);
+
+        let new_index = &mut ctx.accounts.new_authority_index;
+        new_index.authority = new_authority;
+        require!(
+            new_index.player_indices.len() < dapp.max_players_per_authority as usize,
+            ErrorCode::TooManyPlayersForAuthority
+        );
+        new_index.player_indices.push(pda.index);
+
+        let old_authority = pda.authority;
+        pda.authority = new_authority;
+
+        if ctx.accounts.old_authority_index.player_indices.is_empty() {
+            ctx.accounts
+                .old_authority_index
+                .close(ctx.accounts.user.to_account_info())?;
+        }
+
+        emit!(PlayerAuthorityChangedEvent {
+            player_index: pda.index,
+            old_authority,
+            new_authority,
+        });
         Ok(())
     }
 
@@ -262,114 +1337,501 @@ pub mod fancoin {
     /// The "legacy" logic that manipulates `game.minting_agreements`.
     /// If you want your new approach to store per-player approvals, 
     /// you'd rework this logic to push approvals into each `PlayerPda`.
-    pub fn submit_minting_list(
-        ctx: Context<SubmitMintingList>,
+    ///
+    /// The signer's `ValidatorPda` must be passed writable in remaining_accounts,
+    /// since group-distance strikes are recorded on it.
+    ///
+    /// For each attested player pass its `PlayerPda` and writable `PlayerGameState`; the
+    /// state is where bans are checked. States of other players with open agreements may
+    /// be added so those can resolve. Agreements and states are keyed by `PlayerPda.id`.
+    /// A player is minted at most once per epoch per game; agreements without the
+    /// player's state stay pending. Each state may only be passed once.
+    ///
+    /// Every approval needs an attestation for this epoch from one of the game's
+    /// registered servers: an ed25519 precompile instruction in the same transaction
+    /// signing `attestation_message` for the player and its activity score.
+    ///
+    /// An agreement only gathers approvals and resolves under the seed it was created
+    /// with. Agreements from earlier seeds stay behind, as slashing evidence, until
+    /// `prune_expired_agreements` drops them.
+    ///
+    /// Resolved agreements are not paid here. They wait in `game.pending_payouts` for
+    /// the game's challenge window, then `release_payouts` mints the rewards. While that
+    /// queue is full, agreements stay open even when they would resolve.
+    ///
+    /// A player needing a new agreement while the game holds `MAX_MINTING_AGREEMENTS`
+    /// first has the expired ones pruned; if the game is still full, that player is
+    /// skipped so the rest of the list still counts.
+    pub fn submit_minting_list<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitMintingList<'info>>,
         game_number: u32,
-        player_names: Vec<String>,
+        players: Vec<AttestedPlayer>,
     ) -> Result<()> {
-        let game = &mut ctx.accounts.game;
         let validator_signer = &ctx.accounts.validator;
-        let clock = Clock::get()?;
-        let current_time = clock.unix_timestamp;
-
-        // Basic check
-        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        let current_time = Clock::get()?.unix_timestamp;
+        require!(ctx.accounts.game.game_number == game_number, ErrorCode::GameNumberMismatch);
 
         // Make sure the signer is recognized as a validator
-        let mut found_val_pda = false;
+        let mut signer_val_pda: Option<Account<'info, ValidatorPda>> = None;
         for acc_info in ctx.remaining_accounts.iter() {
             if let Ok(val_pda) = Account::<ValidatorPda>::try_from(acc_info) {
                 if val_pda.address == validator_signer.key() {
-                    found_val_pda = true;
+                    // Must be this game's PDA for the signer, not a forged or foreign one
+                    verify_validator_pda(acc_info.key, &val_pda, game_number)?;
+                    signer_val_pda = Some(val_pda);
                     break;
                 }
             }
         }
-        require!(found_val_pda, ErrorCode::ValidatorNotRegistered);
+        let mut signer_val_pda = signer_val_pda.ok_or(ErrorCode::ValidatorNotRegistered)?;
 
-        // Insert or update each MintingAgreement in the old `game.minting_agreements`.
-        for player_name in player_names {
-            if let Some(agreement) =
-                game.minting_agreements.iter_mut().find(|ma| ma.player_name == player_name)
-            {
-                if !agreement.validators.contains(&validator_signer.key()) {
-                    agreement.validators.push(validator_signer.key());
-                }
-            } else {
-                game.minting_agreements.push(MintingAgreement {
-                    player_name,
-                    validators: vec![validator_signer.key()],
-                });
+        // PlayerPdas give each attested name its player id; states are keyed by that id.
+        // A state loaded twice would be persisted twice, the stale copy last.
+        let mut known_players = Vec::new(); // (name, player_id)
+        let mut player_states: Vec<Account<'info, PlayerGameState>> = Vec::new();
+        for acc_info in ctx.remaining_accounts.iter() {
+            if let Ok(player_pda) = Account::<PlayerPda>::try_from(acc_info) {
+                verify_player_pda(acc_info.key, &player_pda, player_pda.index)?;
+                known_players.push((player_pda.name.clone(), player_pda.id));
+            } else if let Ok(state) = Account::<PlayerGameState>::try_from(acc_info) {
+                verify_player_game_state(acc_info.key, &state, game_number)?;
+                require!(
+                    !player_states.iter().any(|s| s.key() == *acc_info.key),
+                    ErrorCode::DuplicatePlayerAccount
+                );
+                player_states.push(state);
             }
         }
 
-        // For example: failover_tolerance
-        let failover_tolerance = calculate_failover_tolerance(game.validator_count as usize);
+        // Names are unique, so the PlayerPda with the attested name is the player it covers
+        let players = players
+            .into_iter()
+            .map(|player| {
+                let (_, player_id) = known_players
+                    .iter()
+                    .find(|(name, _)| *name == player.player_name)
+                    .ok_or(ErrorCode::MissingPlayerAccounts)?;
+                Ok((*player_id, player))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        let mut successful_mints = Vec::new();
-        let mut validator_rewards = Vec::new();  // (validator_pubkey, reward_amt)
-        let mut remaining_agreements = Vec::new();
+        let signed = ed25519_signed_messages(&ctx.accounts.instructions.to_account_info())?;
+        apply_minting_list(
+            &mut ctx.accounts.game,
+            &ctx.accounts.metadata.servers,
+            &signed,
+            &mut signer_val_pda,
+            players,
+            &mut player_states,
+            current_time,
+        )?;
 
-        for agreement in &game.minting_agreements {
-            if agreement.validators.len() >= 2 {
-                // Must have a seed, else skip
-                let seed = match game.last_seed {
-                    Some(s) => s,
-                    None => {
-                        remaining_agreements.push(agreement.clone());
-                        continue;
-                    }
-                };
+        // persist liveness and any strikes recorded against the signer
+        signer_val_pda.exit(ctx.program_id)?;
+
+        for state in &player_states {
+            state.exit(ctx.program_id)?;
+        }
+        Ok(())
+    }
+
+    /// `submit_minting_list` for several games at once, with players named by their
+    /// `PlayerPda` index. For each entry, in order, remaining_accounts hold the game
+    /// (writable), its `GameMetadata` and the signer's `ValidatorPda` (writable), then a
+    /// `PlayerPda` and writable `PlayerGameState` per player. The ed25519 attestations
+    /// are the same as for `submit_minting_list`.
+    ///
+    /// Games may not repeat, nor players within a game's entry.
+    /// A game whose list is rejected is left untouched while the others go through;
+    /// the outcome or error code of each is returned as `Vec<BatchGameResult>` via
+    /// `set_return_data`.
+    pub fn submit_minting_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SubmitMintingBatch<'info>>,
+        entries: Vec<GameBatch>,
+    ) -> Result<()> {
+        require!(entries.len() <= MAX_BATCH_GAMES, ErrorCode::InvalidRange);
+        // Each (game, player) state is loaded once, so a stale copy can't be persisted last
+        for (i, entry) in entries.iter().enumerate() {
+            require!(
+                !entries[..i].iter().any(|e| e.game_number == entry.game_number),
+                ErrorCode::DuplicateBatchGame
+            );
+            for (j, player) in entry.players.iter().enumerate() {
+                require!(
+                    !entry.players[..j].iter().any(|p| p.player_index == player.player_index),
+                    ErrorCode::DuplicatePlayerAccount
+                );
+            }
+        }
+
+        let validator = ctx.accounts.validator.key();
+        let current_time = Clock::get()?.unix_timestamp;
+        let signed = ed25519_signed_messages(&ctx.accounts.instructions.to_account_info())?;
+
+        let mut accounts = ctx.remaining_accounts.iter();
+        let mut next_account = || accounts.next().ok_or(ErrorCode::MissingBatchAccounts);
+        let mut results = Vec::with_capacity(entries.len());
+        for entry in entries {
+            let game_number = entry.game_number;
+            let mut game = Account::<Game>::try_from(next_account()?)?;
+            require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+            let metadata = Account::<GameMetadata>::try_from(next_account()?)?;
+            require!(metadata.game_number == game_number, ErrorCode::GameNumberMismatch);
+            let val_info = next_account()?;
+            let mut val_pda = Account::<ValidatorPda>::try_from(val_info)?;
+            verify_validator_pda(val_info.key, &val_pda, game_number)?;
+            require_keys_eq!(val_pda.address, validator, ErrorCode::ValidatorNotRegistered);
 
-                // grouping logic
-                let first_validator = agreement.validators[0];
-                let first_group_id = calculate_group_id(&first_validator, seed)?;
-
-                let mut all_same_group = true;
-                for validator_key in agreement.validators.iter().skip(1) {
-                    let group_id = calculate_group_id(validator_key, seed)?;
-                    let distance = if group_id > first_group_id {
-                        group_id - first_group_id
-                    } else {
-                        first_group_id - group_id
-                    };
-                    if distance > failover_tolerance as u64 {
-                        all_same_group = false;
-                        break;
+            let mut players = Vec::with_capacity(entry.players.len());
+            let mut player_states = Vec::with_capacity(entry.players.len());
+            for player in entry.players {
+                let pda_info = next_account()?;
+                let player_pda = Account::<PlayerPda>::try_from(pda_info)?;
+                verify_player_pda(pda_info.key, &player_pda, player.player_index)?;
+                let state_info = next_account()?;
+                let state = Account::<PlayerGameState>::try_from(state_info)?;
+                verify_player_game_state(state_info.key, &state, game_number)?;
+                require!(state.player_id == player_pda.id, ErrorCode::InvalidSeeds);
+
+                players.push((
+                    player_pda.id,
+                    AttestedPlayer {
+                        player_name: player_pda.name.clone(),
+                        activity_score: player.activity_score,
+                    },
+                ));
+                player_states.push(state);
+            }
+
+            let applied = apply_minting_list(
+                &mut game,
+                &metadata.servers,
+                &signed,
+                &mut val_pda,
+                players,
+                &mut player_states,
+                current_time,
+            );
+            let result = match applied {
+                Ok(outcome) => {
+                    game.exit(ctx.program_id)?;
+                    val_pda.exit(ctx.program_id)?;
+                    for state in &player_states {
+                        state.exit(ctx.program_id)?;
                     }
+                    BatchGameResult { game_number, error: None, outcome }
                 }
-
-                if all_same_group {
-                    successful_mints.push(agreement.player_name.clone());
-                    for validator_key in &agreement.validators {
-                        if let Some(entry) =
-                            validator_rewards.iter_mut().find(|(vk, _)| vk == validator_key)
-                        {
-                            entry.1 += 1_618_000_000;
-                        } else {
-                            validator_rewards.push((*validator_key, 1_618_000_000));
-                        }
+                // Program errors reject just this game; runtime errors fail the batch
+                Err(Error::AnchorError(err)) => {
+                    msg!("Game {} rejected: {}", game_number, err.error_msg);
+                    BatchGameResult {
+                        game_number,
+                        error: Some(err.error_code_number),
+                        outcome: MintingOutcome::default(),
                     }
-                } else {
-                    remaining_agreements.push(agreement.clone());
                 }
-            } else {
-                remaining_agreements.push(agreement.clone());
+                Err(err) => return Err(err),
+            };
+            results.push(result);
+        }
+
+        set_return_data(&results.try_to_vec()?);
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    //  6) Slashing
+    // ------------------------------------------------------------------
+
+    /// Slash a staked validator given on-chain evidence.
+    ///
+    /// - `ConflictingApprovals`: remaining_accounts = [game_a, game_b], one of them the
+    ///   validator's own game. Both games set `exclusive_play`, and both hold a pending
+    ///   agreement for `player_id` that the validator approved in the same slashing epoch.
+    ///   Players of exclusive games are active in one of them at a time; games that don't
+    ///   opt in can't be used as evidence.
+    /// - `RepeatedGroupFailures`: the validator has `MAX_FAILED_APPROVALS` or more approvals
+    ///   outside the player's group, rejected by `submit_minting_list`. Games using
+    ///   `GroupingStrategy::Legacy` never record these.
+    ///
+    /// `SLASH_BPS` of the stake is removed; `REPORTER_REWARD_BPS` of that goes to the
+    /// reporter and the remainder is burned.
+    pub fn slash_validator<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SlashValidator<'info>>,
+        evidence: SlashEvidence,
+    ) -> Result<()> {
+        let validator = ctx.accounts.validator_pda.address;
+
+        match &evidence {
+            SlashEvidence::ConflictingApprovals { player_id } => {
+                require!(ctx.remaining_accounts.len() >= 2, ErrorCode::InvalidEvidence);
+                let game_a = Account::<Game>::try_from(&ctx.remaining_accounts[0])?;
+                let game_b = Account::<Game>::try_from(&ctx.remaining_accounts[1])?;
+                require!(game_a.game_number != game_b.game_number, ErrorCode::InvalidEvidence);
+                let own_game = ctx.accounts.validator_pda.game_number;
+                require!(
+                    own_game == game_a.game_number || own_game == game_b.game_number,
+                    ErrorCode::InvalidEvidence
+                );
+                require!(game_a.exclusive_play && game_b.exclusive_play, ErrorCode::InvalidEvidence);
+
+                // Dated by the validator's own approvals, not by whoever approved first
+                let approved_at_a = find_approval(&game_a, *player_id, &validator)
+                    .ok_or(ErrorCode::InvalidEvidence)?;
+                let approved_at_b = find_approval(&game_b, *player_id, &validator)
+                    .ok_or(ErrorCode::InvalidEvidence)?;
+
+                let epoch = slashing_epoch(approved_at_a);
+                require!(epoch == slashing_epoch(approved_at_b), ErrorCode::InvalidEvidence);
+
+                // One slash per conflicting epoch, so the same evidence can't be replayed.
+                let val_pda = &mut ctx.accounts.validator_pda;
+                require!(
+                    !matches!(val_pda.last_conflict_slash_epoch, Some(e) if e >= epoch),
+                    ErrorCode::AlreadySlashed
+                );
+                val_pda.last_conflict_slash_epoch = Some(epoch);
+
+                emit!(ConflictingApprovalEvidence {
+                    validator,
+                    player_id: *player_id,
+                    game_a: game_a.game_number,
+                    game_b: game_b.game_number,
+                    approved_at_a,
+                    approved_at_b,
+                    epoch,
+                });
+            }
+            SlashEvidence::RepeatedGroupFailures => {
+                let val_pda = &mut ctx.accounts.validator_pda;
+                require!(
+                    val_pda.failed_approvals >= MAX_FAILED_APPROVALS,
+                    ErrorCode::InvalidEvidence
+                );
+                emit!(RepeatedGroupFailureEvidence {
+                    validator,
+                    failed_approvals: val_pda.failed_approvals,
+                });
+                val_pda.failed_approvals = 0;
             }
         }
 
-        // Actually "mint" for each player
-        for player_name in successful_mints {
-            mint_tokens_for_player(game, &player_name, current_time)?;
+        let (slashed, reporter_reward) = slash_stake(
+            &mut ctx.accounts.validator_pda,
+            &ctx.accounts.reporter.to_account_info(),
+            &ctx.accounts.incinerator.to_account_info(),
+        )?;
+        let game = &mut ctx.accounts.game;
+        game.total_stake = game.total_stake.saturating_sub(slashed);
+
+        emit!(ValidatorSlashedEvent {
+            validator,
+            reporter: ctx.accounts.reporter.key(),
+            evidence,
+            slashed,
+            reporter_reward,
+            burned: slashed - reporter_reward,
+            remaining_stake: ctx.accounts.validator_pda.stake,
+        });
+        Ok(())
+    }
+
+    // ------------------------------------------------------------------
+    //  7) Payouts, disputes and pruning
+    // ------------------------------------------------------------------
+
+    /// Mint every undisputed payout whose challenge window has passed. Anyone may crank
+    /// this. Nothing is released while the game is blacklisted.
+    pub fn release_payouts(ctx: Context<ReleasePayouts>, game_number: u32) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(game.status != GameStatus::Blacklisted, ErrorCode::GameIsBlacklisted);
+        let now = Clock::get()?.unix_timestamp;
+
+        let (due, waiting): (Vec<PendingPayout>, Vec<PendingPayout>) =
+            std::mem::take(&mut game.pending_payouts)
+                .into_iter()
+                .partition(|payout| payout.dispute.is_none() && now >= payout.release_at);
+        game.pending_payouts = waiting;
+        for payout in due {
+            pay_out(game, payout, now)?;
         }
+        Ok(())
+    }
+
+    /// Drop unresolved agreements older than the game's `agreement_ttl_epochs`, emitting
+    /// an `AgreementExpiredEvent` for each. Anyone may crank this.
+    pub fn prune_expired_agreements(ctx: Context<PruneExpiredAgreements>, game_number: u32) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        let (epoch, _) = game.epoch_at(Clock::get()?.unix_timestamp)?;
+
+        prune_agreements(game, epoch);
+        Ok(())
+    }
+
+    /// Dispute a queued payout before its challenge window closes. Any validator of the
+    /// game with at least `MIN_VALIDATOR_STAKE` may dispute, pointing `evidence` at what
+    /// backs the claim. The payout then waits for the DApp owners to rule on it.
+    ///
+    /// Payouts are found by `PlayerPda.id` and epoch, since a name can change hands.
+    pub fn dispute_payout(
+        ctx: Context<DisputePayout>,
+        game_number: u32,
+        player_id: u64,
+        epoch: u64,
+        evidence: String,
+    ) -> Result<()> {
+        let game = &mut ctx.accounts.game;
+        let disputer = ctx.accounts.validator.key();
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        require!(
+            ctx.accounts.validator_pda.stake >= MIN_VALIDATOR_STAKE,
+            ErrorCode::InsufficientStake
+        );
+        require!(evidence.len() <= MAX_DISPUTE_EVIDENCE_LEN, ErrorCode::EvidenceTooLong);
+
+        let now = Clock::get()?.unix_timestamp;
+        let payout = game
+            .pending_payouts
+            .iter_mut()
+            .find(|p| p.player_id == player_id && p.epoch == epoch)
+            .ok_or(ErrorCode::PayoutNotFound)?;
+        require!(payout.dispute.is_none(), ErrorCode::PayoutAlreadyDisputed);
+        require!(now < payout.release_at, ErrorCode::ChallengeWindowClosed);
+        payout.dispute = Some(Dispute { disputer, evidence: evidence.clone(), disputed_at: now });
+
+        emit!(PayoutDisputedEvent {
+            game_number,
+            player_id,
+            player_name: payout.player_name.clone(),
+            epoch,
+            disputer,
+            evidence,
+        });
+        Ok(())
+    }
+
+    /// Rule on a disputed payout. `Pay` mints it right away. `Reject` drops it, hands its
+    /// emission back to the epoch cap and takes it out of the player's `PlayerGameState`,
+    /// which must then be passed. A DApp owner acting alone may rule; otherwise owners use
+    /// a `ResolveDispute` proposal.
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        game_number: u32,
+        player_id: u64,
+        epoch: u64,
+        ruling: DisputeRuling,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer), ErrorCode::Unauthorized);
+        let game = &mut ctx.accounts.game;
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        apply_dispute_ruling(
+            game,
+            ctx.accounts.player_game_state.as_mut(),
+            player_id,
+            epoch,
+            ruling,
+            signer,
+        )
+    }
+
+    // ------------------------------------------------------------------
+    //  8) Player bans and appeals
+    // ------------------------------------------------------------------
 
-        // Reward each validator
-        for (validator_key, reward_amt) in validator_rewards {
-            mint_tokens(game, &validator_key, reward_amt);
+    /// Ban a player from one game, until `expires_at` or for good. Banned players get no
+    /// approvals and their agreements are dropped when they would resolve. Banning again
+    /// replaces the reason and expiry and clears any appeal. The player's
+    /// `PlayerGameState` must exist; `init_player_game_state` is open to anyone.
+    /// Allowed for the game's creator and a DApp owner.
+    pub fn ban_player(
+        ctx: Context<BanPlayer>,
+        game_number: u32,
+        player_index: u32,
+        reason_code: u16,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+        let now = Clock::get()?.unix_timestamp;
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > now, ErrorCode::InvalidTimestamp);
         }
 
-        // keep leftover
-        game.minting_agreements = remaining_agreements;
+        let ban = &mut ctx.accounts.ban;
+        ban.game_number = game_number;
+        ban.player_id = ctx.accounts.player_pda.id;
+        ban.bump = ctx.bumps.ban;
+        ban.reason_code = reason_code;
+        ban.banned_by = signer;
+        ban.banned_at = now;
+        ban.expires_at = expires_at;
+        ban.appeal = None;
+        ctx.accounts.player_game_state.banned_until = Some(expires_at.unwrap_or(i64::MAX));
+
+        emit!(PlayerBannedEvent { game_number, player_index, reason_code, expires_at, banned_by: signer });
+        Ok(())
+    }
+
+    /// Lift a ban, also the way to grant an appeal. The ban's rent goes to the signer.
+    /// Allowed for the game's creator and a DApp owner.
+    pub fn unban_player(ctx: Context<UnbanPlayer>, game_number: u32, player_index: u32) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        ctx.accounts.player_game_state.banned_until = None;
+        emit!(PlayerUnbannedEvent { game_number, player_index, unbanned_by: signer });
+        Ok(())
+    }
+
+    /// Appeal a ban, as the player's authority. One appeal may be pending at a time;
+    /// the game admin answers with `unban_player` or `dismiss_ban_appeal`.
+    pub fn appeal_ban(
+        ctx: Context<AppealBan>,
+        game_number: u32,
+        player_index: u32,
+        message: String,
+    ) -> Result<()> {
+        require!(message.len() <= MAX_APPEAL_MESSAGE_LEN, ErrorCode::AppealTooLong);
+        let ban = &mut ctx.accounts.ban;
+        require!(ban.appeal.is_none(), ErrorCode::AppealPending);
+        ban.appeal = Some(BanAppeal {
+            requested_at: Clock::get()?.unix_timestamp,
+            message: message.clone(),
+        });
+
+        emit!(BanAppealedEvent {
+            game_number,
+            player_index,
+            authority: ctx.accounts.authority.key(),
+            message,
+        });
+        Ok(())
+    }
+
+    /// Turn down a pending appeal, leaving the ban in place. The player may appeal again.
+    /// Allowed for the game's creator and a DApp owner.
+    pub fn dismiss_ban_appeal(ctx: Context<ReviewBanAppeal>, game_number: u32, player_index: u32) -> Result<()> {
+        let dapp = &ctx.accounts.dapp;
+        let game = &ctx.accounts.game;
+        let signer = ctx.accounts.signer.key();
+        require!(dapp.can_act_alone(&signer) || game.creator == signer, ErrorCode::Unauthorized);
+        require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+
+        let ban = &mut ctx.accounts.ban;
+        require!(ban.appeal.is_some(), ErrorCode::NoAppealPending);
+        ban.appeal = None;
+
+        emit!(BanAppealDismissedEvent { game_number, player_index, dismissed_by: signer });
         Ok(())
     }
 }
@@ -380,86 +1842,813 @@ pub mod fancoin {
 
 #[account]
 pub struct DApp {
-    pub owner: Pubkey,
+    /// The first `owners_count` entries are owners; the rest are `Pubkey::default()`
+    pub owners: [Pubkey; MAX_DAPP_OWNERS],
+    pub owners_count: u8,
+    /// Owner approvals a `Proposal` needs before it can be executed
+    pub threshold: u8,
+    /// Live players; `PlayerPda` indexes run densely from 0 to this
     pub global_player_count: u32,
+    pub max_players_per_authority: u8,
+    /// Seconds a requested reward-address change waits before it can be finalized
+    pub reward_change_delay: i64,
+    /// Id of the next proposal
+    pub proposal_count: u64,
+    /// `PlayerPda.id` of the next registered player
+    pub next_player_id: u64,
+    /// Lowest game number a new game may take; numbers are never reused, so state
+    /// keyed by a closed game's number can't leak into a new game
+    pub next_game_number: u32,
 }
 impl DApp {
-    pub const LEN: usize = 8 + 32 + 4;
+    pub const LEN: usize = 8 + 32 * MAX_DAPP_OWNERS + 1 + 1 + 4 + 1 + 8 + 8 + 8 + 4;
+
+    pub fn is_owner(&self, key: &Pubkey) -> bool {
+        self.owners[..self.owners_count as usize].contains(key)
+    }
+
+    /// With a threshold of one, an owner may call admin instructions directly.
+    /// Above that, every owner action goes through a `Proposal`.
+    pub fn can_act_alone(&self, key: &Pubkey) -> bool {
+        self.threshold <= 1 && self.is_owner(key)
+    }
+}
+
+/// An owner action waiting for approvals, seeded by [b"proposal", id].
+#[account]
+pub struct Proposal {
+    pub id: u64,
+    pub bump: u8,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+    /// Owners that approved, proposer included
+    pub approvals: Vec<Pubkey>,
+    pub created_at: i64,
+    /// Last slot the proposal can be approved or executed in
+    pub expires_at_slot: u64,
+}
+impl Proposal {
+    pub const LEN: usize = 8 + 8 + 1 + 32 + ProposalAction::LEN + (4 + 32 * MAX_DAPP_OWNERS) + 8 + 8;
+}
+
+/// What a `Proposal` does when executed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub enum ProposalAction {
+    CreateGame { game_number: u32, description: String, creator: Pubkey },
+    SetGameStatus { game_number: u32, new_status: GameStatus, reason: String },
+    ConfigureGameEpochs { game_number: u32, epoch_length: i64, punch_in_length: i64, reveal_length: i64 },
+    SetQuorumPolicy { game_number: u32, quorum_policy: QuorumPolicy },
+    SetGroupingStrategy { game_number: u32, grouping_strategy: GroupingStrategy },
+    SetRewardConfig { game_number: u32, reward_config: RewardConfig },
+    SetInactivityThreshold { game_number: u32, inactivity_threshold: i64 },
+    RetireGame { game_number: u32, reason: String },
+    SetChallengeWindow { game_number: u32, challenge_window: i64 },
+    SetAgreementTtl { game_number: u32, agreement_ttl_epochs: u64 },
+    ResolveDispute { game_number: u32, player_id: u64, epoch: u64, ruling: DisputeRuling },
+    SetRewardChangeDelay { delay: i64 },
+    SetMaxPlayersPerAuthority { max_players: u8 },
+    AddOwner { owner: Pubkey },
+    RemoveOwner { owner: Pubkey },
+    SetThreshold { threshold: u8 },
+    SetExclusivePlay { game_number: u32, exclusive_play: bool },
+}
+impl ProposalAction {
+    // CreateGame is the largest variant
+    pub const LEN: usize = 1 + 4 + (4 + MAX_GAME_DESCRIPTION_LEN) + 32;
+
+    /// Reject strings that would not fit in `LEN`.
+    pub fn check_lengths(&self) -> Result<()> {
+        match self {
+            ProposalAction::CreateGame { description, .. } => {
+                require!(description.len() <= MAX_GAME_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong)
+            }
+            ProposalAction::SetGameStatus { reason, .. } | ProposalAction::RetireGame { reason, .. } => {
+                require!(reason.len() <= MAX_STATUS_REASON_LEN, ErrorCode::ReasonTooLong)
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Number of the game a `CreateGame` action creates.
+    pub fn created_game_number(&self) -> Option<u32> {
+        match self {
+            ProposalAction::CreateGame { game_number, .. } => Some(*game_number),
+            _ => None,
+        }
+    }
 }
 
 #[account]
 pub struct Game {
     pub game_number: u32,
     pub validator_count: u32,
-    pub status: u8,
+    pub status: GameStatus,
+    /// Studio behind the game, set by the owner creating it; may edit its description
+    /// and metadata but not move it into or out of a privileged status
+    pub creator: Pubkey,
     pub description: String,
 
     pub last_seed: Option<u64>,
     pub last_punch_in_time: Option<i64>,
 
-    /// Legacy leftover array for minting agreements
+    /// Each epoch opens with a punch-in window of `punch_in_length` seconds,
+    /// followed by the mint window for the rest of `epoch_length`.
+    pub epoch_length: i64,
+    pub punch_in_length: i64,
+    /// Reveal window between punch-in and mint.
+    pub reveal_length: i64,
+    /// Epoch `epoch_base` begins at `epoch_start`.
+    pub epoch_start: i64,
+    pub epoch_base: u64,
+    /// Epoch of the current commit-reveal round. `last_seed` is only set once it is finalized.
+    pub seed_epoch: Option<u64>,
+    /// XOR of the secrets revealed so far this round.
+    pub seed_accumulator: [u8; 32],
+    pub commit_count: u32,
+    pub reveal_count: u32,
+    /// Slot whose hash is mixed into this round's seed; the first produced slot at or
+    /// after it is used if it was skipped. 0 until `finalize_seed` fixes it
+    pub seed_slot: u64,
+
+    /// Idle seconds after which a validator can be evicted.
+    pub inactivity_threshold: i64,
+
+    /// Approvals needed before an agreement resolves
+    pub quorum_policy: QuorumPolicy,
+    /// How approvers are checked for being in the same group
+    pub grouping_strategy: GroupingStrategy,
+    /// Sum of `ValidatorPda.stake` over this game's validators
+    pub total_stake: u64,
+
+    pub reward_config: RewardConfig,
+    /// Tokens paid out so far in `emission_epoch`
+    pub emission_epoch: u64,
+    pub epoch_player_emission: u64,
+    pub epoch_validator_emission: u64,
+    pub epoch_creator_emission: u64,
+    /// Seconds a resolved agreement stays open to disputes before it pays out
+    pub challenge_window: i64,
+    /// Epochs an unresolved agreement is kept before it can be pruned
+    pub agreement_ttl_epochs: u64,
+    /// Players can't be active in this and another exclusive game at the same time, so
+    /// approving one player in both is slashable
+    pub exclusive_play: bool,
+
+    /// Open agreements, at most `MAX_MINTING_AGREEMENTS`
     pub minting_agreements: Vec<MintingAgreement>,
+    /// Resolved agreements waiting out their challenge window or a dispute ruling,
+    /// at most `MAX_PENDING_PAYOUTS`
+    pub pending_payouts: Vec<PendingPayout>,
 }
 impl Game {
-    // If you store a big dynamic array, you must allocate enough space 
-    // or do dynamic re-alloc. 
-    // This is just a minimal guess that may cause "failed to serialize" if outgrown.
+    // Stays under the 10 KiB limit for accounts created through `init`
     pub const LEN: usize = 
         8               // anchor disc
         + (4 + 4 + 1)   // game_number, validator_count, status
+        + 32            // creator
         + (4 + 64)      // description up to 64 bytes
         + 9 + 9         // last_seed, last_punch_in_time
-        // plus space for the minting_agreements vector
-        // e.g. 4 + 5*(4 + 32 + 4 + (32*3)) or something 
-        // depends on how many you want to store.
+        + 8 + 8 + 8 + 8 // epoch_length, punch_in_length, epoch_start, epoch_base
+        + 8             // reveal_length
+        + 9             // seed_epoch
+        + 32 + 4 + 4    // seed_accumulator, commit_count, reveal_count
+        + 8             // seed_slot
+        + 8             // inactivity_threshold
+        + QuorumPolicy::LEN + 8 // quorum_policy, total_stake
+        + GroupingStrategy::LEN
+        + RewardConfig::LEN
+        + 8 + 8 + 8     // emission_epoch, epoch_player_emission, epoch_validator_emission
+        + 8             // epoch_creator_emission
+        + 8 + 8         // challenge_window, agreement_ttl_epochs
+        + 1             // exclusive_play
+        + (4 + MAX_MINTING_AGREEMENTS * MintingAgreement::LEN)
+        + (4 + MAX_PENDING_PAYOUTS * PendingPayout::LEN)
     ;
+
+    /// Epoch number at `now`, and seconds elapsed since that epoch began.
+    pub fn epoch_at(&self, now: i64) -> Result<(u64, i64)> {
+        require!(now >= self.epoch_start, ErrorCode::InvalidTimestamp);
+        let elapsed = now - self.epoch_start;
+        let epoch = self.epoch_base + (elapsed / self.epoch_length) as u64;
+        Ok((epoch, elapsed % self.epoch_length))
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct MintingAgreement {
-    pub player_name: String,
-    pub validators: Vec<Pubkey>,
+/// Lifecycle of a game. Serialized as a single byte: 0, 1, 2, 3.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameStatus {
+    Probationary,
+    Whitelisted,
+    Blacklisted,
+    /// Terminal; only reachable through `retire_game`.
+    Retired,
+}
+impl GameStatus {
+    /// Allowed transitions:
+    /// - Probationary => Whitelisted | Blacklisted
+    /// - Whitelisted  => Probationary | Blacklisted
+    /// - Blacklisted  => Probationary (reinstated on probation, never straight to whitelisted)
+    /// - Retired      => nothing
+    pub fn can_transition_to(self, next: GameStatus) -> bool {
+        use GameStatus::*;
+        matches!(
+            (self, next),
+            (Probationary, Whitelisted)
+                | (Probationary, Blacklisted)
+                | (Whitelisted, Probationary)
+                | (Whitelisted, Blacklisted)
+                | (Blacklisted, Probationary)
+        )
+    }
+
+    /// Scale a full reward by this status: whitelisted games pay in full,
+    /// probationary games pay `PROBATIONARY_REWARD_BPS`, blacklisted games pay nothing.
+    pub fn scale_reward(self, amount: u64) -> u64 {
+        let bps = match self {
+            GameStatus::Whitelisted => 10_000,
+            GameStatus::Probationary => PROBATIONARY_REWARD_BPS,
+            GameStatus::Blacklisted | GameStatus::Retired => 0,
+        };
+        (amount as u128 * bps as u128 / 10_000) as u64
+    }
+
+    /// Statuses only the DApp owners may move a game into or out of.
+    pub fn is_privileged(self) -> bool {
+        matches!(self, GameStatus::Whitelisted | GameStatus::Blacklisted)
+    }
 }
 
+/// Off-chain description of a game and the servers validators should watch,
+/// seeded by [b"game_metadata", game_number].
 #[account]
-pub struct PlayerPda {
+pub struct GameMetadata {
+    pub game_number: u32,
+    pub bump: u8,
     pub name: String,
-    pub authority: Pubkey,
-    pub reward_address: Pubkey,
-    pub last_name_change: Option<i64>,
-    pub last_reward_change: Option<i64>,
-
-    // Possibly store your "per-player" minting approvals or recent minted data here
-    // e.g. pub approvals: Vec<SomeStruct> if you prefer that approach.
+    /// Points at a JSON manifest describing the game
+    pub uri: String,
+    pub servers: Vec<GameServer>,
 }
-impl PlayerPda {
-    // If you store more data, expand LEN
+impl GameMetadata {
     pub const LEN: usize = 8
-        + (4 + 32) // name
-        + 32       // authority
-        + 32       // reward_address
-        + 9        // last_name_change
-        + 9;       // last_reward_change
+        + 4 + 1
+        + (4 + MAX_GAME_NAME_LEN)
+        + (4 + MAX_METADATA_URI_LEN)
+        + 4 + MAX_GAME_SERVERS * GameServer::LEN;
 }
 
-#[account]
-pub struct ValidatorPda {
-    pub address: Pubkey,
-    pub last_activity: i64,
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GameServer {
+    pub endpoint: String,
+    /// Key the server signs its reports with
+    pub signing_key: Pubkey,
 }
-impl ValidatorPda {
-    pub const LEN: usize = 8 + 32 + 8;
+impl GameServer {
+    pub const LEN: usize = (4 + MAX_SERVER_ENDPOINT_LEN) + 32;
 }
 
-#[account]
-pub struct Player {
-    // If you still want a separate "Player" legacy approach (like older code).
-    pub name: String,
-    pub address: Pubkey,
-    pub reward_address: Pubkey,
-    pub last_minted: Option<i64>,
-    pub last_name_change: Option<i64>,
-    pub last_reward_address_change: Option<i64>,
+/// A player named in `submit_minting_list`, with the activity score its attestation covers.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AttestedPlayer {
+    pub player_name: String,
+    pub activity_score: u64,
+}
+
+/// One game's part of a `submit_minting_batch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct GameBatch {
+    pub game_number: u32,
+    pub players: Vec<BatchPlayer>,
+}
+
+/// A player in a `GameBatch`, by `PlayerPda` index, with its attested activity score.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct BatchPlayer {
+    pub player_index: u32,
+    pub activity_score: u64,
+}
+
+/// What one validator's minting list did for a game.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MintingOutcome {
+    /// Approvals from the validator that were new
+    pub approvals: u32,
+    /// Agreements that resolved and were queued for payout
+    pub queued: u32,
+    /// Agreements left waiting for more approvals
+    pub pending: u32,
+}
+
+/// Per-game entry in the return data of `submit_minting_batch`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchGameResult {
+    pub game_number: u32,
+    /// Error code the game's list was rejected with; its accounts were left unchanged
+    pub error: Option<u32>,
+    pub outcome: MintingOutcome,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MintingAgreement {
+    /// `PlayerPda.id` of the player; agreements are matched by it, not by name
+    pub player_id: u64,
+    pub player_name: String,
+    pub validators: Vec<Pubkey>,
+    /// When each of `validators` approved, in the same order; dates slashing evidence
+    pub approved_at: Vec<i64>,
+    /// Time of the first approval
+    pub created_at: i64,
+    /// Stake of the approving validators at the time they approved.
+    pub approved_stake: u64,
+    /// Epoch and seed the agreement was created under; approvals only count under that seed.
+    pub epoch: u64,
+    pub seed: u64,
+}
+impl MintingAgreement {
+    pub const LEN: usize = 8 // player_id
+        + (4 + MAX_PLAYER_NAME_LEN)
+        + (4 + 32 * MAX_AGREEMENT_VALIDATORS)
+        + (4 + 8 * MAX_AGREEMENT_VALIDATORS) // approved_at
+        + 8 + 8 // created_at, approved_stake
+        + 8 + 8; // epoch, seed
+
+    /// Whether the agreement may be pruned during `epoch`.
+    pub fn is_expired(&self, epoch: u64, ttl_epochs: u64) -> bool {
+        epoch >= self.epoch.saturating_add(ttl_epochs)
+    }
+}
+
+/// A resolved agreement, paid out by `release_payouts` once `release_at` passes
+/// undisputed. The rewards are fixed when the agreement resolves.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PendingPayout {
+    pub player_id: u64,
+    pub player_name: String,
+    pub epoch: u64,
+    pub release_at: i64,
+    pub player_reward: u64,
+    /// Approving validators, paid `validator_shares` in the same order
+    pub validators: Vec<Pubkey>,
+    pub validator_shares: Vec<u64>,
+    pub creator_reward: u64,
+    pub dispute: Option<Dispute>,
+}
+impl PendingPayout {
+    pub const LEN: usize = 8 // player_id
+        + (4 + MAX_PLAYER_NAME_LEN)
+        + 8 + 8 + 8 // epoch, release_at, player_reward
+        + (4 + 32 * MAX_AGREEMENT_VALIDATORS)
+        + (4 + 8 * MAX_AGREEMENT_VALIDATORS)
+        + 8 // creator_reward
+        + 1 + Dispute::LEN;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Dispute {
+    pub disputer: Pubkey,
+    /// URI or hash of the evidence, up to `MAX_DISPUTE_EVIDENCE_LEN` bytes
+    pub evidence: String,
+    pub disputed_at: i64,
+}
+impl Dispute {
+    pub const LEN: usize = 32 + (4 + MAX_DISPUTE_EVIDENCE_LEN) + 8;
+}
+
+/// DApp owners' decision on a disputed payout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DisputeRuling {
+    /// The dispute fails; the payout is minted
+    Pay,
+    /// The dispute holds; the payout is dropped
+    Reject,
+}
+
+/// Rewards paid when an agreement resolves, before the game-status multiplier.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RewardConfig {
+    /// Minted to the player per agreement
+    pub player_amount: u64,
+    /// Shared by the approving validators per agreement
+    pub validator_amount: u64,
+    pub validator_split: ValidatorSplit,
+    /// Most tokens (players + validators + creator) the game may emit in one epoch
+    pub epoch_emission_cap: u64,
+    /// Share of each agreement's emission paid to the game creator, in basis points
+    pub creator_share_bps: u16,
+}
+impl RewardConfig {
+    pub const LEN: usize = 8 + 8 + 1 + 8 + 2;
+}
+impl Default for RewardConfig {
+    fn default() -> Self {
+        RewardConfig {
+            player_amount: DEFAULT_PLAYER_REWARD,
+            validator_amount: DEFAULT_VALIDATOR_REWARD_POOL,
+            validator_split: ValidatorSplit::Even,
+            epoch_emission_cap: u64::MAX,
+            creator_share_bps: 0,
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ValidatorSplit {
+    /// Every approver gets the same share.
+    Even,
+    /// The i-th of n approvers gets weight n - i, so earlier approvers earn more.
+    ByApprovalOrder,
+}
+impl ValidatorSplit {
+    /// Split `pool` across `n` approvers in approval order. Rounding dust goes to the first.
+    pub fn shares(self, n: usize, pool: u64) -> Vec<u64> {
+        if n == 0 {
+            return Vec::new();
+        }
+        let weights: Vec<u64> = match self {
+            ValidatorSplit::Even => vec![1; n],
+            ValidatorSplit::ByApprovalOrder => (1..=n as u64).rev().collect(),
+        };
+        let total_weight: u64 = weights.iter().sum();
+        let mut shares: Vec<u64> = weights
+            .iter()
+            .map(|w| (pool as u128 * *w as u128 / total_weight as u128) as u64)
+            .collect();
+        let paid: u64 = shares.iter().sum();
+        shares[0] += pool - paid;
+        shares
+    }
+}
+
+/// How many approvals a `MintingAgreement` needs, on top of the group-distance check.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QuorumPolicy {
+    /// At least `count` validators (2 to `MAX_AGREEMENT_VALIDATORS`).
+    Absolute { count: u32 },
+    /// At least `numerator / denominator` of the game's validators, rounded up, and never
+    /// more than `MAX_AGREEMENT_VALIDATORS`.
+    Fraction { numerator: u32, denominator: u32 },
+    /// Approvers hold at least `threshold_bps` of the game's total stake. Only the first
+    /// `MAX_AGREEMENT_VALIDATORS` approvers count.
+    StakeWeighted { threshold_bps: u16 },
+}
+impl QuorumPolicy {
+    pub const LEN: usize = 1 + 4 + 4;
+
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            QuorumPolicy::Absolute { count } => {
+                count >= 2 && count as usize <= MAX_AGREEMENT_VALIDATORS
+            }
+            QuorumPolicy::Fraction { numerator, denominator } => {
+                numerator > 0 && numerator <= denominator
+            }
+            QuorumPolicy::StakeWeighted { threshold_bps } => {
+                threshold_bps > 0 && threshold_bps <= 10_000
+            }
+        }
+    }
+
+    pub fn is_met(&self, approvals: usize, approved_stake: u64, validator_count: u32, total_stake: u64) -> bool {
+        match *self {
+            QuorumPolicy::Absolute { count } => approvals >= count as usize,
+            QuorumPolicy::Fraction { numerator, denominator } => {
+                let needed = (validator_count as u64 * numerator as u64)
+                    .div_ceil(denominator as u64);
+                approvals as u64 >= needed.clamp(1, MAX_AGREEMENT_VALIDATORS as u64)
+            }
+            QuorumPolicy::StakeWeighted { threshold_bps } => {
+                total_stake > 0
+                    && approved_stake as u128 * 10_000
+                        >= total_stake as u128 * threshold_bps as u128
+            }
+        }
+    }
+}
+
+/// How `submit_minting_list` decides which validators may approve a player.
+/// Validators and players each get a position (`group_of`, with `player_group_key` for
+/// players); a validator is in a player's group when their `distance` is at most
+/// `tolerance`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GroupingStrategy {
+    /// Raw distance between 64-bit group hashes, within `calculate_failover_tolerance`.
+    /// At that scale almost no validator ever matches a player, so approvals outside
+    /// the player's group are not counted as failures. New games never start with it.
+    Legacy,
+    /// Hashes are spread over ceil(validator_count / group_size) buckets;
+    /// only validators in the same bucket match.
+    Bucketed { group_size: u32 },
+    /// Hashes are placed on a ring with one slot per validator; validators match
+    /// within `radius` slots of each other, wrapping around.
+    Ring { radius: u32 },
+}
+impl GroupingStrategy {
+    pub const LEN: usize = 1 + 4;
+
+    pub fn is_valid(&self) -> bool {
+        match *self {
+            GroupingStrategy::Legacy => true,
+            GroupingStrategy::Bucketed { group_size } => group_size >= 2,
+            GroupingStrategy::Ring { radius } => radius >= 1,
+        }
+    }
+
+    /// Position of `address` under `seed`; for `Legacy` this is `calculate_group_id`.
+    pub fn group_of(&self, address: &Pubkey, seed: u64, validator_count: u32) -> Result<u64> {
+        let id = calculate_group_id(address, seed)?;
+        Ok(match *self {
+            GroupingStrategy::Legacy => id,
+            GroupingStrategy::Bucketed { group_size } => {
+                let buckets = (validator_count as u64).div_ceil(group_size as u64).max(1);
+                id % buckets
+            }
+            GroupingStrategy::Ring { .. } => id % (validator_count as u64).max(1),
+        })
+    }
+
+    /// Distance between two positions returned by `group_of`.
+    pub fn distance(&self, a: u64, b: u64, validator_count: u32) -> u64 {
+        let d = a.abs_diff(b);
+        match *self {
+            GroupingStrategy::Ring { .. } => d.min((validator_count as u64).max(1) - d),
+            _ => d,
+        }
+    }
+
+    /// Largest distance still counted as the same group.
+    pub fn tolerance(&self, validator_count: u32) -> u64 {
+        match *self {
+            GroupingStrategy::Legacy => calculate_failover_tolerance(validator_count as usize) as u64,
+            GroupingStrategy::Bucketed { .. } => 0,
+            GroupingStrategy::Ring { radius } => radius as u64,
+        }
+    }
+
+    pub fn group_distance(&self, a: &Pubkey, b: &Pubkey, seed: u64, validator_count: u32) -> Result<u64> {
+        let group_a = self.group_of(a, seed, validator_count)?;
+        let group_b = self.group_of(b, seed, validator_count)?;
+        Ok(self.distance(group_a, group_b, validator_count))
+    }
+
+    /// Distance from `validator` to the group of player `player_id` under `seed`.
+    pub fn player_distance(&self, validator: &Pubkey, player_id: u64, seed: u64, validator_count: u32) -> Result<u64> {
+        self.group_distance(validator, &player_group_key(player_id), seed, validator_count)
+    }
+}
+
+/// Seeded by [b"player_pda", index].
+#[account]
+pub struct PlayerPda {
+    /// Dense position among the registered players; can change on `deregister_player`
+    pub index: u32,
+    pub bump: u8,
+    /// Never changes or gets reused; per-game accounts are keyed by it
+    pub id: u64,
+    pub name: String,
+    pub authority: Pubkey,
+    pub reward_address: Pubkey,
+    pub last_name_change: Option<i64>,
+    pub last_reward_change: Option<i64>,
+    /// Reward address queued by `request_reward_address_change`
+    pub pending_reward_address: Option<Pubkey>,
+    pub pending_reward_requested_at: Option<i64>,
+    /// Open `PlayerGameState`s; must be zero to deregister
+    pub game_state_count: u32,
+
+    // Possibly store your "per-player" minting approvals or recent minted data here
+    // e.g. pub approvals: Vec<SomeStruct> if you prefer that approach.
+}
+impl PlayerPda {
+    // If you store more data, expand LEN
+    pub const LEN: usize = 8
+        + 4 + 1    // index, bump
+        + 8        // id
+        + (4 + MAX_PLAYER_NAME_LEN) // name
+        + 32       // authority
+        + 32       // reward_address
+        + 9        // last_name_change
+        + 9        // last_reward_change
+        + 33       // pending_reward_address
+        + 9        // pending_reward_requested_at
+        + 4;       // game_state_count
+}
+
+/// Claim on a player name, seeded by [b"player_name", name]. Names are unique, so a
+/// game server's attestation for a name covers exactly one player.
+#[account]
+pub struct PlayerName {
+    pub player_id: u64,
+    pub bump: u8,
+}
+impl PlayerName {
+    pub const LEN: usize = 8 + 8 + 1;
+}
+
+/// Minting history of one player in one game,
+/// seeded by [b"player_game", game_number, player_id].
+#[account]
+pub struct PlayerGameState {
+    pub game_number: u32,
+    pub player_id: u64,
+    pub bump: u8,
+    pub last_minted_epoch: Option<u64>,
+    pub total_minted: u64,
+    /// Validator approvals over all resolved agreements for this player
+    pub lifetime_approvals: u64,
+    /// Mirrors the player's `PlayerBan` in this game, so minting can enforce it
+    /// without the ban account; `i64::MAX` for a permanent ban
+    pub banned_until: Option<i64>,
+    /// Paid the rent; gets it back when the state is closed
+    pub payer: Pubkey,
+}
+impl PlayerGameState {
+    pub const LEN: usize = 8 + 4 + 8 + 1 + 9 + 8 + 8 + 9 + 32;
+
+    pub fn is_banned(&self, now: i64) -> bool {
+        matches!(self.banned_until, Some(until) if now < until)
+    }
+}
+
+/// A player's exclusion from one game, seeded by [b"player_ban", game_number, player_id].
+#[account]
+pub struct PlayerBan {
+    pub game_number: u32,
+    pub player_id: u64,
+    pub bump: u8,
+    /// Game-defined code for why the player was banned
+    pub reason_code: u16,
+    pub banned_by: Pubkey,
+    pub banned_at: i64,
+    /// When the ban lapses; `None` is permanent
+    pub expires_at: Option<i64>,
+    /// Filed by the player's authority for the game admin to review
+    pub appeal: Option<BanAppeal>,
+}
+impl PlayerBan {
+    pub const LEN: usize = 8 + 4 + 8 + 1 + 2 + 32 + 8 + 9 + (1 + BanAppeal::LEN);
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BanAppeal {
+    pub requested_at: i64,
+    pub message: String,
+}
+impl BanAppeal {
+    pub const LEN: usize = 8 + (4 + MAX_APPEAL_MESSAGE_LEN);
+}
+
+/// Reverse lookup seeded by [b"player_authority", authority]:
+/// the indexes of the `PlayerPda`s this authority controls.
+#[account]
+pub struct PlayerAuthorityIndex {
+    pub authority: Pubkey,
+    pub player_indices: Vec<u32>,
+}
+impl PlayerAuthorityIndex {
+    pub const LEN: usize = 8 + 32 + 4 + 4 * MAX_PLAYERS_PER_AUTHORITY;
+}
+
+/// Seeded by [b"validator", game_number, address].
+#[account]
+pub struct ValidatorPda {
+    pub game_number: u32,
+    /// Position in the game's `ValidatorIndex` list.
+    pub index: u32,
+    pub bump: u8,
+    pub address: Pubkey,
+    pub last_activity: i64,
+    /// Lamports held by this PDA on top of its rent, subject to slashing.
+    pub stake: u64,
+    /// Approvals that failed the group-distance check since the last slash.
+    pub failed_approvals: u32,
+    pub last_conflict_slash_epoch: Option<i64>,
+    /// Commit-reveal state for the game's seed.
+    pub seed_commitment: Option<[u8; 32]>,
+    pub commitment_epoch: Option<u64>,
+}
+impl ValidatorPda {
+    pub const LEN: usize = 8 + 32 + 8
+        + 4 + 4 + 1 // game_number, index, bump
+        + 8   // stake
+        + 4   // failed_approvals
+        + 9   // last_conflict_slash_epoch
+        + 33  // seed_commitment
+        + 9;  // commitment_epoch
+}
+
+/// Secondary index seeded by [b"validator_index", game_number, index],
+/// pointing at the validator registered under that index.
+#[account]
+pub struct ValidatorIndex {
+    pub validator: Pubkey,
+    pub bump: u8,
+}
+impl ValidatorIndex {
+    pub const LEN: usize = 8 + 32 + 1;
+}
+
+/// One row of a pagination page.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PdaPageEntry {
+    pub index: u32,
+    /// Address of the listed PDA
+    pub key: Pubkey,
+    /// Player name; empty for validators
+    pub name: String,
+    /// Player reward address, or the validator's own address
+    pub reward_address: Pubkey,
+    /// Validator liveness; players don't track it
+    pub last_activity: Option<i64>,
+}
+
+/// Return data of the pagination instructions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PdaPage {
+    pub entries: Vec<PdaPageEntry>,
+    /// Where to start the next page, or None when the list is exhausted
+    pub next_index: Option<u32>,
+}
+impl PdaPage {
+    /// Serialized size of the empty page: vec length + Some(next_index)
+    const BASE_LEN: usize = 4 + 5;
+
+    fn new() -> Self {
+        PdaPage { entries: Vec::new(), next_index: None }
+    }
+
+    fn serialized_len(&self) -> usize {
+        Self::BASE_LEN
+            + self
+                .entries
+                .iter()
+                .map(|e| 4 + 32 + 4 + e.name.len() + 32 + 9)
+                .sum::<usize>()
+    }
+
+    /// Append `entry` unless the page would outgrow the return-data limit.
+    fn try_push(&mut self, entry: PdaPageEntry) -> bool {
+        let entry_len = 4 + 32 + 4 + entry.name.len() + 32 + 9;
+        if self.serialized_len() + entry_len > MAX_RETURN_DATA {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    /// Set the continuation cursor after the page has been filled.
+    fn finish(&mut self, start_index: u32, clamped_end: u32, total: u32) {
+        let next = start_index + self.entries.len() as u32;
+        self.next_index = if next < clamped_end || clamped_end < total {
+            Some(next)
+        } else {
+            None
+        };
+    }
+}
+
+/// Return data of `get_validator_group_info`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct GroupAssignment {
+    pub seed: u64,
+    /// Largest group distance still treated as the same group
+    pub failover_tolerance: u64,
+    /// `GroupingStrategy::group_of` each queried validator, in query order
+    pub group_ids: Vec<u64>,
+    /// Upper triangle of the pairwise matrix: (0,1), (0,2), .., (1,2), ..
+    pub compatible: Vec<bool>,
+}
+impl GroupAssignment {
+    /// Whether validators `i` and `j` of the query fall in the same group.
+    pub fn is_compatible(&self, i: usize, j: usize) -> bool {
+        if i == j {
+            return true;
+        }
+        let (i, j) = (i.min(j), i.max(j));
+        let n = self.group_ids.len();
+        // rows before i hold (n-1) + (n-2) + .. + (n-i) pairs
+        let row_start = i * (2 * n - i - 1) / 2;
+        self.compatible[row_start + (j - i - 1)]
+    }
+}
+
+/// Evidence accepted by `slash_validator`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum SlashEvidence {
+    /// `PlayerPda.id` of the player approved in both games
+    ConflictingApprovals { player_id: u64 },
+    RepeatedGroupFailures,
+}
+
+#[account]
+pub struct Player {
+    // If you still want a separate "Player" legacy approach (like older code).
+    pub name: String,
+    pub address: Pubkey,
+    pub reward_address: Pubkey,
+    pub last_minted: Option<i64>,
+    pub last_name_change: Option<i64>,
+    pub last_reward_address_change: Option<i64>,
 }
 impl Player {
     pub const MAX_NAME_LEN: usize = 16;
@@ -479,6 +2668,14 @@ pub struct InitializeDapp<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct UpdateDapp<'info> {
+    #[account(mut, seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    pub signer: Signer<'info>,
+}
+
 #[derive(Accounts)]
 pub struct RelinquishOwnership<'info> {
     #[account(mut, seeds = [b"dapp"], bump)]
@@ -488,6 +2685,97 @@ pub struct RelinquishOwnership<'info> {
     pub signer: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct ProposeAction<'info> {
+    #[account(mut, seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [b"proposal", &dapp.proposal_count.to_le_bytes()[..]],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ApproveProposal<'info> {
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(mut, seeds = [b"proposal", &proposal_id.to_le_bytes()[..]], bump = proposal.bump)]
+    pub proposal: Account<'info, Proposal>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ExecuteProposal<'info> {
+    #[account(mut, seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", &proposal_id.to_le_bytes()[..]],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// The game targeted by a game action (checked in the handler)
+    #[account(mut)]
+    pub game: Option<Account<'info, Game>>,
+
+    /// The game a `CreateGame` action creates; any other action must leave it out
+    #[account(
+        init,
+        payer = executor,
+        space = Game::LEN,
+        seeds = [b"game", &proposal.action.created_game_number().unwrap_or_default().to_le_bytes()[..]],
+        bump,
+        constraint = proposal.action.created_game_number().is_some() @ ErrorCode::UnexpectedGameAccount
+    )]
+    pub new_game: Option<Account<'info, Game>>,
+
+    /// The disputed player's state, for a `ResolveDispute` rejection (checked in the handler)
+    #[account(mut)]
+    pub player_game_state: Option<Account<'info, PlayerGameState>>,
+
+    /// CHECK: Only receives the proposal's rent
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub executor: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct CancelProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"proposal", &proposal_id.to_le_bytes()[..]],
+        bump = proposal.bump,
+        close = proposer
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    /// CHECK: Only receives the proposal's rent
+    #[account(mut, address = proposal.proposer)]
+    pub proposer: UncheckedAccount<'info>,
+
+    pub signer: Signer<'info>,
+}
+
 #[derive(Accounts)]
 #[instruction(game_number: u32, description: String)]
 pub struct InitializeGame<'info> {
@@ -500,7 +2788,7 @@ pub struct InitializeGame<'info> {
     )]
     pub game: Account<'info, Game>,
 
-    #[account(seeds = [b"dapp"], bump)]
+    #[account(mut, seeds = [b"dapp"], bump)]
     pub dapp: Account<'info, DApp>,
 
     #[account(mut)]
@@ -523,38 +2811,221 @@ pub struct UpdateGameStatus<'info> {
 
 #[derive(Accounts)]
 #[instruction(game_number: u32)]
-pub struct PunchIn<'info> {
-    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+pub struct CloseGame<'info> {
+    #[account(
+        mut,
+        seeds = [b"game", &game_number.to_le_bytes()],
+        bump,
+        close = creator
+    )]
     pub game: Account<'info, Game>,
 
-    #[account(mut)]
-    pub validator: Signer<'info>,
-    pub system_program: Program<'info, System>,
+    /// CHECK: Closed in the handler if it holds a `GameMetadata`
+    #[account(mut, seeds = [b"game_metadata", &game_number.to_le_bytes()[..]], bump)]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Only receives rent
+    #[account(mut, address = game.creator)]
+    pub creator: UncheckedAccount<'info>,
+
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    pub signer: Signer<'info>,
 }
 
 #[derive(Accounts)]
 #[instruction(game_number: u32)]
-pub struct RegisterValidatorPda<'info> {
-    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+pub struct SetGameMetadata<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
     pub game: Account<'info, Game>,
 
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
     #[account(
-        init,
-        payer = user,
-        space = ValidatorPda::LEN,
-        seeds = [b"validator", &game_number.to_le_bytes()[..], &game.validator_count.to_le_bytes()[..]],
+        init_if_needed,
+        payer = signer,
+        space = GameMetadata::LEN,
+        seeds = [b"game_metadata", &game_number.to_le_bytes()[..]],
         bump
     )]
-    pub validator_pda: Account<'info, ValidatorPda>,
+    pub metadata: Account<'info, GameMetadata>,
 
     #[account(mut)]
-    pub user: Signer<'info>,
+    pub signer: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
-/// Register a brand-new player as a PDA
 #[derive(Accounts)]
-pub struct RegisterPlayerPda<'info> {
+#[instruction(game_number: u32)]
+pub struct UpdateGameServers<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        mut,
+        seeds = [b"game_metadata", &game_number.to_le_bytes()[..]],
+        bump = metadata.bump
+    )]
+    pub metadata: Account<'info, GameMetadata>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct PunchIn<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"validator", &game_number.to_le_bytes()[..], validator.key().as_ref()],
+        bump = validator_pda.bump
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    #[account(mut)]
+    pub validator: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct RevealSeed<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"validator", &game_number.to_le_bytes()[..], validator.key().as_ref()],
+        bump = validator_pda.bump
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    pub validator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct FinalizeSeed<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    /// CHECK: Read raw for the hash of `game.seed_slot`
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct RegisterValidatorPda<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        init,
+        payer = user,
+        space = ValidatorPda::LEN,
+        seeds = [b"validator", &game_number.to_le_bytes()[..], user.key().as_ref()],
+        bump
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    #[account(
+        init,
+        payer = user,
+        space = ValidatorIndex::LEN,
+        seeds = [b"validator_index", &game_number.to_le_bytes()[..], &game.validator_count.to_le_bytes()[..]],
+        bump
+    )]
+    pub validator_index: Account<'info, ValidatorIndex>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct EvictInactiveValidator<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"validator", &game_number.to_le_bytes()[..], validator_pda.address.as_ref()],
+        bump = validator_pda.bump,
+        close = cranker
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    /// The evicted validator's slot in the index
+    #[account(
+        mut,
+        seeds = [b"validator_index", &game_number.to_le_bytes()[..], &validator_pda.index.to_le_bytes()[..]],
+        bump = validator_index.bump
+    )]
+    pub validator_index: Account<'info, ValidatorIndex>,
+
+    /// The last index entry, moved into the vacated slot (checked in the handler)
+    #[account(mut)]
+    pub last_validator_index: Option<Account<'info, ValidatorIndex>>,
+
+    /// The validator pointed at by `last_validator_index` (checked in the handler)
+    #[account(mut)]
+    pub moved_validator_pda: Option<Account<'info, ValidatorPda>>,
+
+    /// CHECK: Only receives the evicted validator's stake
+    #[account(mut, address = validator_pda.address)]
+    pub validator_wallet: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct WithdrawValidator<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"validator", &game_number.to_le_bytes()[..], validator.key().as_ref()],
+        bump = validator_pda.bump,
+        close = validator
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    /// The validator's slot in the index
+    #[account(
+        mut,
+        seeds = [b"validator_index", &game_number.to_le_bytes()[..], &validator_pda.index.to_le_bytes()[..]],
+        bump = validator_index.bump
+    )]
+    pub validator_index: Account<'info, ValidatorIndex>,
+
+    /// The last index entry, moved into the vacated slot (checked in the handler)
+    #[account(mut)]
+    pub last_validator_index: Option<Account<'info, ValidatorIndex>>,
+
+    /// The validator pointed at by `last_validator_index` (checked in the handler)
+    #[account(mut)]
+    pub moved_validator_pda: Option<Account<'info, ValidatorPda>>,
+
+    #[account(mut)]
+    pub validator: Signer<'info>,
+}
+
+/// Register a brand-new player as a PDA
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct RegisterPlayerPda<'info> {
     #[account(mut, seeds = [b"dapp"], bump)]
     pub dapp: Account<'info, DApp>,
 
@@ -570,109 +3041,1525 @@ pub struct RegisterPlayerPda<'info> {
     )]
     pub player_pda: Account<'info, PlayerPda>,
 
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = PlayerAuthorityIndex::LEN,
+        seeds = [b"player_authority", authority.key().as_ref()],
+        bump
+    )]
+    pub authority_index: Account<'info, PlayerAuthorityIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = PlayerName::LEN,
+        seeds = [b"player_name", name.as_bytes()],
+        bump
+    )]
+    pub player_name: Account<'info, PlayerName>,
+
+    /// Controls the new player
+    pub authority: Signer<'info>,
+
     #[account(mut)]
     pub user: Signer<'info>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(game_number: u32)]
-pub struct SubmitMintingList<'info> {
-    /// The game in question
-    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
-    pub game: Account<'info, Game>,
+#[instruction(player_index: u32)]
+pub struct DeregisterPlayer<'info> {
+    #[account(mut, seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
 
-    /// The signer claiming to be a validator
-    pub validator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(
+        mut,
+        seeds = [b"player_name", player_pda.name.as_bytes()],
+        bump = player_name.bump,
+        close = authority
+    )]
+    pub player_name: Account<'info, PlayerName>,
+
+    #[account(mut, seeds = [b"player_authority", authority.key().as_ref()], bump)]
+    pub authority_index: Account<'info, PlayerAuthorityIndex>,
+
+    /// The player at the last index, moved into the vacated slot
+    #[account(
+        mut,
+        seeds = [b"player_pda", &dapp.global_player_count.saturating_sub(1).to_le_bytes()[..]],
+        bump = last_player_pda.bump
+    )]
+    pub last_player_pda: Option<Account<'info, PlayerPda>>,
+
+    /// The moved player's reverse index, if another authority holds it (checked in the handler)
+    #[account(mut)]
+    pub moved_authority_index: Option<Account<'info, PlayerAuthorityIndex>>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
 }
 
-// Name or reward cooldown updates
 #[derive(Accounts)]
-pub struct UpdatePlayerNameCooldown<'info> {
+#[instruction(new_authority: Pubkey)]
+pub struct UpdatePlayerAuthority<'info> {
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        mut,
+        seeds = [b"player_pda", &player_pda.index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(mut, seeds = [b"player_authority", user.key().as_ref()], bump)]
+    pub old_authority_index: Account<'info, PlayerAuthorityIndex>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = PlayerAuthorityIndex::LEN,
+        seeds = [b"player_authority", new_authority.as_ref()],
+        bump
+    )]
+    pub new_authority_index: Account<'info, PlayerAuthorityIndex>,
+
     #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32, player_index: u32)]
+pub struct InitPlayerGameState<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        mut,
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
     pub player_pda: Account<'info, PlayerPda>,
 
+    #[account(
+        init,
+        payer = user,
+        space = PlayerGameState::LEN,
+        seeds = [b"player_game", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump
+    )]
+    pub player_game_state: Account<'info, PlayerGameState>,
+
     #[account(mut)]
     pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32, player_index: u32)]
+pub struct ClosePlayerGameState<'info> {
+    /// CHECK: Loaded in the handler unless the game has been closed
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(
+        mut,
+        seeds = [b"player_game", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = player_game_state.bump,
+        close = payer
+    )]
+    pub player_game_state: Account<'info, PlayerGameState>,
+
+    /// The player's ban in this game, if `banned_until` is set
+    #[account(
+        mut,
+        seeds = [b"player_ban", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = ban.bump
+    )]
+    pub ban: Option<Account<'info, PlayerBan>>,
+
+    /// CHECK: Receives the ban's rent (checked in the handler)
+    #[account(mut)]
+    pub ban_refund: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Only receives the state's rent
+    #[account(mut, address = player_game_state.payer)]
+    pub payer: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32, player_index: u32)]
+pub struct BanPlayer<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(
+        mut,
+        seeds = [b"player_game", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = player_game_state.bump
+    )]
+    pub player_game_state: Account<'info, PlayerGameState>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        space = PlayerBan::LEN,
+        seeds = [b"player_ban", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump
+    )]
+    pub ban: Account<'info, PlayerBan>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
+
 #[derive(Accounts)]
-pub struct UpdatePlayerRewardCooldown<'info> {
+#[instruction(game_number: u32, player_index: u32)]
+pub struct UnbanPlayer<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(
+        mut,
+        seeds = [b"player_game", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = player_game_state.bump
+    )]
+    pub player_game_state: Account<'info, PlayerGameState>,
+
+    #[account(
+        mut,
+        seeds = [b"player_ban", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = ban.bump,
+        close = signer
+    )]
+    pub ban: Account<'info, PlayerBan>,
+
     #[account(mut)]
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32, player_index: u32)]
+pub struct AppealBan<'info> {
+    #[account(
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump,
+        has_one = authority @ ErrorCode::Unauthorized
+    )]
     pub player_pda: Account<'info, PlayerPda>,
 
-    #[account(mut)]
-    pub user: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"player_ban", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = ban.bump
+    )]
+    pub ban: Account<'info, PlayerBan>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32, player_index: u32)]
+pub struct ReviewBanAppeal<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        seeds = [b"player_pda", &player_index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(
+        mut,
+        seeds = [b"player_ban", &game_number.to_le_bytes()[..], &player_pda.id.to_le_bytes()[..]],
+        bump = ban.bump
+    )]
+    pub ban: Account<'info, PlayerBan>,
+
+    pub signer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct StakeValidator<'info> {
+    #[account(
+        mut,
+        seeds = [b"validator", &validator_pda.game_number.to_le_bytes()[..], user.key().as_ref()],
+        bump = validator_pda.bump
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    #[account(mut, seeds = [b"game", &validator_pda.game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SlashValidator<'info> {
+    #[account(
+        mut,
+        seeds = [b"validator", &validator_pda.game_number.to_le_bytes()[..], validator_pda.address.as_ref()],
+        bump = validator_pda.bump
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    #[account(mut, seeds = [b"game", &validator_pda.game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    /// Whoever submits the evidence receives the reporter reward
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    /// CHECK: Burn destination for the non-reporter share of the slash
+    #[account(mut, address = incinerator::ID)]
+    pub incinerator: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct SubmitMintingList<'info> {
+    /// The game in question
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    /// The signer claiming to be a validator
+    pub validator: Signer<'info>,
+
+    /// Registered servers whose attestations are accepted
+    #[account(seeds = [b"game_metadata", &game_number.to_le_bytes()[..]], bump = metadata.bump)]
+    pub metadata: Account<'info, GameMetadata>,
+
+    /// CHECK: Instructions sysvar, read for the ed25519 attestations
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+/// The per-game accounts come in remaining_accounts; see `submit_minting_batch`.
+#[derive(Accounts)]
+pub struct SubmitMintingBatch<'info> {
+    pub validator: Signer<'info>,
+
+    /// CHECK: Instructions sysvar, read for the ed25519 attestations
+    #[account(address = sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct ReleasePayouts<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct PruneExpiredAgreements<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct DisputePayout<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(
+        seeds = [b"validator", &game_number.to_le_bytes()[..], validator.key().as_ref()],
+        bump = validator_pda.bump
+    )]
+    pub validator_pda: Account<'info, ValidatorPda>,
+
+    pub validator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct ResolveDispute<'info> {
+    #[account(mut, seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    /// The disputed player's state, needed to reject (checked in the handler)
+    #[account(mut)]
+    pub player_game_state: Option<Account<'info, PlayerGameState>>,
+
+    pub signer: Signer<'info>,
+}
+
+// Name or reward cooldown updates
+#[derive(Accounts)]
+#[instruction(new_name: String)]
+pub struct UpdatePlayerNameCooldown<'info> {
+    #[account(mut)]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    /// Claim on the current name, released by the rename
+    #[account(
+        mut,
+        seeds = [b"player_name", player_pda.name.as_bytes()],
+        bump = old_player_name.bump,
+        close = user
+    )]
+    pub old_player_name: Account<'info, PlayerName>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = PlayerName::LEN,
+        seeds = [b"player_name", new_name.as_bytes()],
+        bump
+    )]
+    pub new_player_name: Account<'info, PlayerName>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+#[derive(Accounts)]
+pub struct ChangePlayerReward<'info> {
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        mut,
+        seeds = [b"player_pda", &player_pda.index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+#[derive(Accounts)]
+pub struct FinalizePlayerReward<'info> {
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+
+    #[account(
+        mut,
+        seeds = [b"player_pda", &player_pda.index.to_le_bytes()[..]],
+        bump = player_pda.bump
+    )]
+    pub player_pda: Account<'info, PlayerPda>,
+}
+
+// Pagination
+#[derive(Accounts)]
+pub struct GetPlayerListPdaPage<'info> {
+    #[account(seeds = [b"dapp"], bump)]
+    pub dapp: Account<'info, DApp>,
+}
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct GetValidatorGroupInfo<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_number: u32)]
+pub struct GetValidatorListPdaPage<'info> {
+    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
+    pub game: Account<'info, Game>,
+}
+
+// ------------------------------------------------------------------
+// Errors & Utility
+// ------------------------------------------------------------------
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized.")]
+    Unauthorized,
+    #[msg("Not in punch-in period.")]
+    NotInPunchInPeriod,
+    #[msg("Not in mint period.")]
+    NotInMintPeriod,
+    #[msg("Insufficient stake.")]
+    InsufficientStake,
+    #[msg("Player name already exists.")]
+    PlayerNameExists,
+    #[msg("Validator not registered.")]
+    ValidatorNotRegistered,
+    #[msg("Hash conversion error.")]
+    HashConversionError,
+    #[msg("Invalid timestamp.")]
+    InvalidTimestamp,
+    #[msg("Game number mismatch.")]
+    GameNumberMismatch,
+    #[msg("Game status already set.")]
+    GameStatusAlreadySet,
+    #[msg("Game is blacklisted.")]
+    GameIsBlacklisted,
+    /// No longer returned: payouts are scaled by `GameStatus::scale_reward`. Kept so
+    /// later error codes keep their numbers.
+    #[msg("Game not whitelisted.")]
+    GameNotWhitelisted,
+    #[msg("Name cooldown active.")]
+    NameChangeCooldown,
+    #[msg("Invalid seeds.")]
+    InvalidSeeds,
+    #[msg("Invalid range.")]
+    InvalidRange,
+    #[msg("No seed generated.")]
+    NoSeed,
+    /// No longer returned. Kept so later error codes keep their numbers.
+    #[msg("Account already expanded.")]
+    AlreadyExpanded,
+    #[msg("Arithmetic overflow.")]
+    Overflow,
+    #[msg("Slashing evidence is invalid.")]
+    InvalidEvidence,
+    #[msg("Validator already slashed for this evidence.")]
+    AlreadySlashed,
+    #[msg("Punch-in and reveal windows must be positive and fit in the epoch.")]
+    InvalidEpochConfig,
+    #[msg("Not in reveal period.")]
+    NotInRevealPeriod,
+    #[msg("Validator already committed this epoch.")]
+    AlreadyCommitted,
+    #[msg("Reveal does not match commitment.")]
+    InvalidReveal,
+    #[msg("Seed already finalized.")]
+    SeedAlreadyFinalized,
+    #[msg("Validator is still active.")]
+    ValidatorStillActive,
+    #[msg("Missing or mismatched validator index accounts.")]
+    MissingValidatorIndex,
+    #[msg("Game status transition not allowed.")]
+    InvalidStatusTransition,
+    #[msg("Reason is too long.")]
+    ReasonTooLong,
+    #[msg("Invalid quorum policy.")]
+    InvalidQuorumPolicy,
+    #[msg("Authority already controls the maximum number of players.")]
+    TooManyPlayersForAuthority,
+    #[msg("Reward address cooldown active.")]
+    RewardChangeCooldown,
+    #[msg("A reward address change is already pending.")]
+    RewardChangePending,
+    #[msg("No reward address change is pending.")]
+    NoRewardChangePending,
+    #[msg("Reward address change is still timelocked.")]
+    RewardChangeTimelocked,
+    #[msg("Invalid reward configuration.")]
+    InvalidRewardConfig,
+    #[msg("Description is too long.")]
+    DescriptionTooLong,
+    #[msg("Name is too long.")]
+    NameTooLong,
+    #[msg("URI is too long.")]
+    UriTooLong,
+    #[msg("Server endpoint is too long.")]
+    EndpointTooLong,
+    #[msg("Game already has the maximum number of servers.")]
+    TooManyServers,
+    #[msg("Server is already registered.")]
+    ServerAlreadyRegistered,
+    #[msg("Server not found.")]
+    ServerNotFound,
+    #[msg("Game is retired.")]
+    GameIsRetired,
+    #[msg("Game is not retired.")]
+    GameNotRetired,
+    #[msg("Game still has validators.")]
+    GameHasValidators,
+    #[msg("Owners array is full.")]
+    OwnersFull,
+    #[msg("That pubkey is already an owner.")]
+    AlreadyOwner,
+    #[msg("Owner not found.")]
+    OwnerNotFound,
+    #[msg("Threshold must be between 1 and the number of owners.")]
+    InvalidThreshold,
+    #[msg("Owner already approved this proposal.")]
+    AlreadyApproved,
+    #[msg("Proposal does not have enough approvals.")]
+    ThresholdNotMet,
+    #[msg("Game account required for this proposal.")]
+    MissingGameAccount,
+    #[msg("Invalid grouping strategy.")]
+    InvalidGroupingStrategy,
+    #[msg("Missing or invalid game-server attestation.")]
+    InvalidAttestation,
+    #[msg("No pending payout for that player and epoch.")]
+    PayoutNotFound,
+    #[msg("Payout is already disputed.")]
+    PayoutAlreadyDisputed,
+    #[msg("Challenge window has closed.")]
+    ChallengeWindowClosed,
+    #[msg("Payout is not disputed.")]
+    PayoutNotDisputed,
+    #[msg("Evidence is too long.")]
+    EvidenceTooLong,
+    #[msg("Game still has pending payouts.")]
+    PayoutsPending,
+    #[msg("Player game state required.")]
+    MissingPlayerGameState,
+    #[msg("Missing or misordered batch accounts.")]
+    MissingBatchAccounts,
+    #[msg("Game appears twice in the batch.")]
+    DuplicateBatchGame,
+    #[msg("Appeal message is too long.")]
+    AppealTooLong,
+    #[msg("An appeal is already pending.")]
+    AppealPending,
+    #[msg("No appeal is pending.")]
+    NoAppealPending,
+    #[msg("Missing or mismatched player accounts.")]
+    MissingPlayerAccounts,
+    #[msg("Seed target slot has not passed yet.")]
+    SeedSlotPending,
+    /// No longer returned: `finalize_seed` fixes a new slot instead. Kept so later
+    /// error codes keep their numbers.
+    #[msg("Seed target slot is no longer in SlotHashes.")]
+    SlotHashUnavailable,
+    #[msg("Player account passed more than once.")]
+    DuplicatePlayerAccount,
+    #[msg("Proposal has expired.")]
+    ProposalExpired,
+    /// No longer returned: `submit_minting_list` skips players it has no room for.
+    /// Kept so later error codes keep their numbers.
+    #[msg("Game has too many open agreements; prune expired ones first.")]
+    TooManyAgreements,
+    #[msg("Game number already used; game numbers are never reused.")]
+    GameNumberUsed,
+    #[msg("Only a CreateGame proposal takes a new game account.")]
+    UnexpectedGameAccount,
+    #[msg("Player still has game states; close them first.")]
+    PlayerHasGameStates,
+    #[msg("Player is banned from this game.")]
+    PlayerBanned,
+    #[msg("Player has a payout pending in this game.")]
+    PlayerHasPendingPayout,
+    #[msg("Player is not in the authority's player index.")]
+    PlayerNotInAuthorityIndex,
+    #[msg("No players are registered.")]
+    NoPlayersRegistered,
+    #[msg("Missing or mismatched ban accounts.")]
+    MissingBanAccounts,
+}
+
+/// Group ids, failover tolerance and pairwise compatibility of `validators` for `seed`,
+/// in a game with `validator_count` validators using `strategy`. Pure, so clients can
+/// run it off-chain and get the same answer as `get_validator_group_info`.
+pub fn group_assignment(
+    validators: &[Pubkey],
+    seed: u64,
+    validator_count: u32,
+    strategy: GroupingStrategy,
+) -> Result<GroupAssignment> {
+    let failover_tolerance = strategy.tolerance(validator_count);
+    let group_ids = validators
+        .iter()
+        .map(|v| strategy.group_of(v, seed, validator_count))
+        .collect::<Result<Vec<u64>>>()?;
+
+    let mut compatible = Vec::new();
+    for (i, a) in group_ids.iter().enumerate() {
+        for b in group_ids.iter().skip(i + 1) {
+            compatible.push(strategy.distance(*a, *b, validator_count) <= failover_tolerance);
+        }
+    }
+
+    Ok(GroupAssignment { seed, failover_tolerance, group_ids, compatible })
+}
+
+pub fn calculate_failover_tolerance(total_validators: usize) -> usize {
+    let total_groups = total_validators.div_ceil(4);
+    let num_digits = total_groups.to_string().len();
+    num_digits + 1
+}
+
+pub fn calculate_group_id(address: &Pubkey, seed: u64) -> Result<u64> {
+    let mut hasher = Keccak256::new();
+    hasher.update(address.to_bytes());
+    hasher.update(seed.to_le_bytes());
+    let result = hasher.finalize();
+    let bytes: [u8; 8] = result[0..8]
+        .try_into()
+        .map_err(|_| ErrorCode::HashConversionError)?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Stand-in address that places player `player_id` among the validators' groups. Derived
+/// from the id alone, so no approver can choose which group decides on a player.
+pub fn player_group_key(player_id: u64) -> Pubkey {
+    let mut hasher = Keccak256::new();
+    hasher.update(b"player");
+    hasher.update(player_id.to_le_bytes());
+    Pubkey::new_from_array(hasher.finalize().into())
+}
+
+/// Re-derive a `ValidatorPda` address from its stored bump and check it belongs to `game_number`.
+fn verify_validator_pda(key: &Pubkey, val_pda: &ValidatorPda, game_number: u32) -> Result<()> {
+    require!(val_pda.game_number == game_number, ErrorCode::GameNumberMismatch);
+    let derived = Pubkey::create_program_address(
+        &[
+            b"validator",
+            &game_number.to_le_bytes(),
+            val_pda.address.as_ref(),
+            &[val_pda.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidSeeds)?;
+    require_keys_eq!(derived, *key, ErrorCode::InvalidSeeds);
+    Ok(())
+}
+
+/// Re-derive a `PlayerPda` address for `index` from its stored bump.
+fn verify_player_pda(key: &Pubkey, player_pda: &PlayerPda, index: u32) -> Result<()> {
+    require!(player_pda.index == index, ErrorCode::InvalidRange);
+    let derived = Pubkey::create_program_address(
+        &[b"player_pda", &index.to_le_bytes(), &[player_pda.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidSeeds)?;
+    require_keys_eq!(derived, *key, ErrorCode::InvalidSeeds);
+    Ok(())
+}
+
+/// Re-derive a `PlayerGameState` address and check it belongs to `game_number`.
+fn verify_player_game_state(key: &Pubkey, state: &PlayerGameState, game_number: u32) -> Result<()> {
+    require!(state.game_number == game_number, ErrorCode::GameNumberMismatch);
+    let derived = Pubkey::create_program_address(
+        &[
+            b"player_game",
+            &game_number.to_le_bytes(),
+            &state.player_id.to_le_bytes(),
+            &[state.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidSeeds)?;
+    require_keys_eq!(derived, *key, ErrorCode::InvalidSeeds);
+    Ok(())
+}
+
+/// The `Game` passed to `execute_proposal`, checked against the proposal's game number.
+fn proposal_game<'a, 'info>(
+    game: &'a mut Option<Account<'info, Game>>,
+    game_number: u32,
+) -> Result<&'a mut Account<'info, Game>> {
+    let game = game.as_mut().ok_or(ErrorCode::MissingGameAccount)?;
+    require!(game.game_number == game_number, ErrorCode::GameNumberMismatch);
+    Ok(game)
+}
+
+/// One validator's minting list for one game, shared by `submit_minting_list` and
+/// `submit_minting_batch`: checks the mint window and the attestations, records the
+/// approvals and queues the agreements that resolve. Accounts only change in memory;
+/// callers persist them once this succeeds.
+///
+/// `players` pairs each attested player with its `PlayerPda.id`; agreements and
+/// `player_states` are matched by that id.
+fn apply_minting_list<'info>(
+    game: &mut Account<'info, Game>,
+    servers: &[GameServer],
+    signed: &[(Pubkey, Vec<u8>)],
+    signer_val_pda: &mut Account<'info, ValidatorPda>,
+    players: Vec<(u64, AttestedPlayer)>,
+    player_states: &mut [Account<'info, PlayerGameState>],
+    current_time: i64,
+) -> Result<MintingOutcome> {
+    let game_number = game.game_number;
+
+    // Basic check
+    require!(game.status != GameStatus::Blacklisted, ErrorCode::GameIsBlacklisted);
+    require!(game.status != GameStatus::Retired, ErrorCode::GameIsRetired);
+
+    // Only during the mint window, and only once this epoch's seed is revealed
+    let (epoch, offset) = game.epoch_at(current_time)?;
+    require!(
+        offset >= game.punch_in_length + game.reveal_length,
+        ErrorCode::NotInMintPeriod
+    );
+    require!(game.seed_epoch == Some(epoch), ErrorCode::NoSeed);
+    let seed = game.last_seed.ok_or(ErrorCode::NoSeed)?;
+
+    // Every approval must be backed by a server attestation for this epoch. Banned
+    // players get no new approvals; their state, found by player id, must be passed so
+    // a ban can't be dodged by leaving it out.
+    let mut approved = Vec::with_capacity(players.len()); // (player_id, name)
+    for (player_id, player) in players {
+        let message =
+            attestation_message(game_number, epoch, &player.player_name, player.activity_score);
+        require!(
+            signed.iter().any(|(key, signed_message)| *signed_message == message
+                && servers.iter().any(|s| s.signing_key == *key)),
+            ErrorCode::InvalidAttestation
+        );
+        let state = player_states
+            .iter()
+            .find(|state| state.player_id == player_id)
+            .ok_or(ErrorCode::MissingPlayerGameState)?;
+        if state.is_banned(current_time) {
+            msg!("{} is banned from this game; skipping approval", player.player_name);
+            continue;
+        }
+        approved.push((player_id, player.player_name));
+    }
+
+    let validator = signer_val_pda.address;
+    require!(signer_val_pda.stake >= MIN_VALIDATOR_STAKE, ErrorCode::InsufficientStake);
+
+    // Same-group rule for this game
+    let strategy = game.grouping_strategy;
+    let failover_tolerance = strategy.tolerance(game.validator_count);
+
+    // Insert or update each MintingAgreement in `game.minting_agreements`; full
+    // agreements take no more approvals. Only validators in the player's group may
+    // approve. Any other approval is left out, so it can't hold up the agreement, and
+    // counts as a strike against the signer. Legacy groups almost never match, so
+    // honest validators would be struck; no strikes there.
+    let mut new_approvals = 0u32;
+    for (player_id, player_name) in approved {
+        let distance = strategy.player_distance(&validator, player_id, seed, game.validator_count)?;
+        if distance > failover_tolerance {
+            if strategy != GroupingStrategy::Legacy {
+                signer_val_pda.failed_approvals = signer_val_pda.failed_approvals.saturating_add(1);
+                emit!(GroupFailureEvent {
+                    game_number,
+                    validator,
+                    player_id,
+                    player_name,
+                    seed,
+                    distance,
+                    failover_tolerance,
+                    failed_approvals: signer_val_pda.failed_approvals,
+                });
+            }
+            continue;
+        }
+
+        if let Some(agreement) = game
+            .minting_agreements
+            .iter_mut()
+            .find(|ma| ma.player_id == player_id && ma.seed == seed)
+        {
+            if !agreement.validators.contains(&validator)
+                && agreement.validators.len() < MAX_AGREEMENT_VALIDATORS
+            {
+                agreement.validators.push(validator);
+                agreement.approved_at.push(current_time);
+                agreement.approved_stake =
+                    agreement.approved_stake.saturating_add(signer_val_pda.stake);
+                new_approvals += 1;
+            }
+        } else {
+            if game.minting_agreements.len() >= MAX_MINTING_AGREEMENTS {
+                prune_agreements(game, epoch);
+            }
+            if game.minting_agreements.len() >= MAX_MINTING_AGREEMENTS {
+                msg!("Game has {} open agreements; skipping {}", MAX_MINTING_AGREEMENTS, player_name);
+                continue;
+            }
+            game.minting_agreements.push(MintingAgreement {
+                player_id,
+                player_name,
+                validators: vec![validator],
+                approved_at: vec![current_time],
+                created_at: current_time,
+                approved_stake: signer_val_pda.stake,
+                epoch,
+                seed,
+            });
+            new_approvals += 1;
+        }
+    }
+
+    // Probationary games pay reduced rewards
+    let player_gross = game.status.scale_reward(game.reward_config.player_amount);
+    let validator_gross = game.status.scale_reward(game.reward_config.validator_amount);
+    let agreement_emission = player_gross + validator_gross;
+
+    // The creator's cut comes out of both the player reward and the validator pool
+    let creator_bps = game.reward_config.creator_share_bps as u128;
+    let player_cut = (player_gross as u128 * creator_bps / 10_000) as u64;
+    let validator_cut = (validator_gross as u128 * creator_bps / 10_000) as u64;
+    let player_reward = player_gross - player_cut;
+    let validator_pool = validator_gross - validator_cut;
+    let creator_reward = player_cut + validator_cut;
+
+    // Emission totals restart with each epoch
+    let (mut epoch_player_emission, mut epoch_validator_emission, mut epoch_creator_emission) =
+        if game.emission_epoch == epoch {
+            (
+                game.epoch_player_emission,
+                game.epoch_validator_emission,
+                game.epoch_creator_emission,
+            )
+        } else {
+            (0, 0, 0)
+        };
+    let release_at = current_time
+        .checked_add(game.challenge_window)
+        .ok_or(ErrorCode::Overflow)?;
+
+    let mut new_payouts = Vec::new();
+    let mut remaining_agreements = Vec::new();
+    // Agreements that resolve once the payout queue is full wait for `release_payouts`
+    let payout_room = MAX_PENDING_PAYOUTS.saturating_sub(game.pending_payouts.len());
+
+    for agreement in &game.minting_agreements {
+        // Groups are drawn from the seed, so approvals under another seed can't be compared
+        if agreement.seed == seed && agreement.validators.len() >= 2 {
+            // Groups move when validators join or leave, so check every approver again
+            let mut all_same_group = true;
+            for validator_key in &agreement.validators {
+                let distance = strategy.player_distance(
+                    validator_key,
+                    agreement.player_id,
+                    seed,
+                    game.validator_count,
+                )?;
+                if distance > failover_tolerance {
+                    all_same_group = false;
+                    break;
+                }
+            }
+
+            let quorum_met = game.quorum_policy.is_met(
+                agreement.validators.len(),
+                agreement.approved_stake,
+                game.validator_count,
+                game.total_stake,
+            );
+
+            // Agreements that would exceed this epoch's cap wait for the next epoch
+            let within_cap = (epoch_player_emission as u128
+                + epoch_validator_emission as u128
+                + epoch_creator_emission as u128
+                + agreement_emission as u128)
+                <= game.reward_config.epoch_emission_cap as u128;
+
+            if all_same_group && quorum_met && within_cap && new_payouts.len() < payout_room {
+                let Some(state) = player_states
+                    .iter_mut()
+                    .find(|state| state.player_id == agreement.player_id)
+                else {
+                    // Can't enforce the per-epoch limit without the player's state
+                    remaining_agreements.push(agreement.clone());
+                    continue;
+                };
+                if state.last_minted_epoch == Some(epoch) {
+                    msg!("{} already minted this epoch; dropping agreement", agreement.player_name);
+                    continue;
+                }
+                if state.is_banned(current_time) {
+                    msg!("{} is banned from this game; dropping agreement", agreement.player_name);
+                    continue;
+                }
+                state.last_minted_epoch = Some(epoch);
+                state.total_minted = state.total_minted.saturating_add(player_reward);
+                state.lifetime_approvals = state
+                    .lifetime_approvals
+                    .saturating_add(agreement.validators.len() as u64);
+
+                // The cap is reserved now; a rejected dispute gives it back
+                new_payouts.push(PendingPayout {
+                    player_id: agreement.player_id,
+                    player_name: agreement.player_name.clone(),
+                    epoch,
+                    release_at,
+                    player_reward,
+                    validators: agreement.validators.clone(),
+                    validator_shares: game
+                        .reward_config
+                        .validator_split
+                        .shares(agreement.validators.len(), validator_pool),
+                    creator_reward,
+                    dispute: None,
+                });
+                epoch_player_emission += player_reward;
+                epoch_validator_emission += validator_pool;
+                epoch_creator_emission += creator_reward;
+            } else {
+                remaining_agreements.push(agreement.clone());
+            }
+        } else {
+            remaining_agreements.push(agreement.clone());
+        }
+    }
+
+    game.emission_epoch = epoch;
+    game.epoch_player_emission = epoch_player_emission;
+    game.epoch_validator_emission = epoch_validator_emission;
+    game.epoch_creator_emission = epoch_creator_emission;
+
+    // Queue the resolved agreements for the challenge window
+    for payout in &new_payouts {
+        emit!(PayoutQueuedEvent {
+            game_number,
+            player_name: payout.player_name.clone(),
+            epoch,
+            release_at,
+        });
+    }
+
+    let outcome = MintingOutcome {
+        approvals: new_approvals,
+        queued: new_payouts.len() as u32,
+        pending: remaining_agreements.len() as u32,
+    };
+    game.pending_payouts.extend(new_payouts);
+
+    // keep leftover
+    game.minting_agreements = remaining_agreements;
+
+    // liveness; the caller persists the validator's account
+    signer_val_pda.last_activity = current_time;
+    Ok(outcome)
+}
+
+/// Shared by `initialize_game` and a `CreateGame` proposal; callers check authority.
+fn apply_new_game(
+    dapp: &mut DApp,
+    game: &mut Game,
+    game_number: u32,
+    description: String,
+    creator: Pubkey,
+) -> Result<()> {
+    require!(description.len() <= MAX_GAME_DESCRIPTION_LEN, ErrorCode::DescriptionTooLong);
+    require!(game_number >= dapp.next_game_number, ErrorCode::GameNumberUsed);
+    dapp.next_game_number = game_number.checked_add(1).ok_or(ErrorCode::Overflow)?;
+
+    game.game_number = game_number;
+    game.creator = creator;
+    game.description = description;
+    game.status = GameStatus::Probationary;
+    game.validator_count = 0;
+    game.last_seed = None;
+    game.last_punch_in_time = None;
+    game.epoch_length = DEFAULT_EPOCH_SECONDS;
+    game.punch_in_length = DEFAULT_PUNCH_IN_SECONDS;
+    game.reveal_length = DEFAULT_REVEAL_SECONDS;
+    game.epoch_start = Clock::get()?.unix_timestamp;
+    game.epoch_base = 0;
+    game.seed_epoch = None;
+    game.seed_accumulator = [0u8; 32];
+    game.commit_count = 0;
+    game.reveal_count = 0;
+    game.seed_slot = 0;
+    game.inactivity_threshold = DEFAULT_INACTIVITY_SECONDS;
+    game.quorum_policy = QuorumPolicy::Absolute { count: 2 };
+    game.grouping_strategy = GroupingStrategy::Bucketed { group_size: DEFAULT_GROUP_SIZE };
+    game.total_stake = 0;
+    game.reward_config = RewardConfig::default();
+    game.emission_epoch = 0;
+    game.epoch_player_emission = 0;
+    game.epoch_validator_emission = 0;
+    game.epoch_creator_emission = 0;
+    game.challenge_window = DEFAULT_CHALLENGE_WINDOW_SECONDS;
+    game.agreement_ttl_epochs = DEFAULT_AGREEMENT_TTL_EPOCHS;
+    game.exclusive_play = false;
+    // Legacy leftover array in the Game account
+    game.minting_agreements = Vec::new(); 
+    game.pending_payouts = Vec::new();
+    Ok(())
+}
+
+/// Shared by the direct admin instructions and `execute_proposal`; callers check authority.
+fn apply_game_status(game: &mut Game, new_status: GameStatus, reason: String, changed_by: Pubkey) -> Result<()> {
+    require!(reason.len() <= MAX_STATUS_REASON_LEN, ErrorCode::ReasonTooLong);
+    let old_status = game.status;
+    require!(old_status != new_status, ErrorCode::GameStatusAlreadySet);
+    require!(
+        old_status.can_transition_to(new_status),
+        ErrorCode::InvalidStatusTransition
+    );
+
+    game.status = new_status;
+    emit!(GameStatusChangedEvent {
+        game_number: game.game_number,
+        old_status,
+        new_status,
+        reason,
+        changed_by,
+    });
+    Ok(())
+}
+
+/// The change starts a fresh epoch right away, so the current seed is dropped.
+fn apply_epoch_config(game: &mut Game, epoch_length: i64, punch_in_length: i64, reveal_length: i64) -> Result<()> {
+    require!(
+        punch_in_length > 0
+            && reveal_length > 0
+            && punch_in_length + reveal_length < epoch_length,
+        ErrorCode::InvalidEpochConfig
+    );
+
+    let now = Clock::get()?.unix_timestamp;
+    let (epoch, _) = game.epoch_at(now)?;
+    game.epoch_base = epoch + 1;
+    game.epoch_start = now;
+    game.epoch_length = epoch_length;
+    game.punch_in_length = punch_in_length;
+    game.reveal_length = reveal_length;
+    game.last_seed = None;
+    game.seed_epoch = None;
+    Ok(())
+}
+
+fn apply_grouping_strategy(game: &mut Game, grouping_strategy: GroupingStrategy) -> Result<()> {
+    require!(grouping_strategy.is_valid(), ErrorCode::InvalidGroupingStrategy);
+    game.grouping_strategy = grouping_strategy;
+    Ok(())
+}
+
+fn apply_quorum_policy(game: &mut Game, quorum_policy: QuorumPolicy) -> Result<()> {
+    require!(quorum_policy.is_valid(), ErrorCode::InvalidQuorumPolicy);
+    game.quorum_policy = quorum_policy;
+    Ok(())
+}
+
+fn apply_reward_config(game: &mut Game, reward_config: RewardConfig) -> Result<()> {
+    require!(
+        reward_config.player_amount.checked_add(reward_config.validator_amount).is_some(),
+        ErrorCode::Overflow
+    );
+    require!(reward_config.creator_share_bps <= 10_000, ErrorCode::InvalidRewardConfig);
+    game.reward_config = reward_config;
+    Ok(())
+}
+
+fn apply_inactivity_threshold(game: &mut Game, inactivity_threshold: i64) -> Result<()> {
+    require!(inactivity_threshold > 0, ErrorCode::InvalidTimestamp);
+    game.inactivity_threshold = inactivity_threshold;
+    Ok(())
+}
+
+fn apply_retirement(game: &mut Game, reason: String, retired_by: Pubkey) -> Result<()> {
+    require!(reason.len() <= MAX_STATUS_REASON_LEN, ErrorCode::ReasonTooLong);
+    require!(game.status != GameStatus::Retired, ErrorCode::GameStatusAlreadySet);
+
+    let old_status = game.status;
+    let dropped_agreements = game.minting_agreements.len() as u32;
+    game.status = GameStatus::Retired;
+    game.minting_agreements = Vec::new();
+    game.last_seed = None;
+    game.seed_epoch = None;
+
+    emit!(GameRetiredEvent {
+        game_number: game.game_number,
+        old_status,
+        dropped_agreements,
+        validator_count: game.validator_count,
+        reason,
+        retired_by,
+    });
+    Ok(())
+}
+
+fn apply_challenge_window(game: &mut Game, challenge_window: i64) -> Result<()> {
+    require!(challenge_window >= 0, ErrorCode::InvalidTimestamp);
+    game.challenge_window = challenge_window;
+    Ok(())
+}
+
+fn apply_agreement_ttl(game: &mut Game, agreement_ttl_epochs: u64) -> Result<()> {
+    require!(agreement_ttl_epochs >= 1, ErrorCode::InvalidRange);
+    game.agreement_ttl_epochs = agreement_ttl_epochs;
+    Ok(())
+}
+
+fn apply_exclusive_play(game: &mut Game, exclusive_play: bool) -> Result<()> {
+    game.exclusive_play = exclusive_play;
+    Ok(())
+}
+
+/// Drop the game's agreements that have expired by `epoch`, emitting an
+/// `AgreementExpiredEvent` for each.
+fn prune_agreements(game: &mut Game, epoch: u64) {
+    let ttl_epochs = game.agreement_ttl_epochs;
+    let (expired, kept): (Vec<MintingAgreement>, Vec<MintingAgreement>) =
+        std::mem::take(&mut game.minting_agreements)
+            .into_iter()
+            .partition(|agreement| agreement.is_expired(epoch, ttl_epochs));
+    game.minting_agreements = kept;
+
+    for agreement in expired {
+        emit!(AgreementExpiredEvent {
+            game_number: game.game_number,
+            player_name: agreement.player_name,
+            created_epoch: agreement.epoch,
+            approvals: agreement.validators.len() as u32,
+            pruned_epoch: epoch,
+        });
+    }
+}
+
+/// Shared by `resolve_dispute` and `execute_proposal`; callers check authority.
+fn apply_dispute_ruling<'info>(
+    game: &mut Account<'info, Game>,
+    player_game_state: Option<&mut Account<'info, PlayerGameState>>,
+    player_id: u64,
+    epoch: u64,
+    ruling: DisputeRuling,
+    ruled_by: Pubkey,
+) -> Result<()> {
+    let pos = game
+        .pending_payouts
+        .iter()
+        .position(|p| p.player_id == player_id && p.epoch == epoch)
+        .ok_or(ErrorCode::PayoutNotFound)?;
+    require!(game.pending_payouts[pos].dispute.is_some(), ErrorCode::PayoutNotDisputed);
+    let payout = game.pending_payouts.remove(pos);
+    let player_name = payout.player_name.clone();
+
+    match ruling {
+        DisputeRuling::Pay => pay_out(game, payout, Clock::get()?.unix_timestamp)?,
+        DisputeRuling::Reject => {
+            let state = player_game_state.ok_or(ErrorCode::MissingPlayerGameState)?;
+            verify_player_game_state(&state.key(), state, game.game_number)?;
+            require!(state.player_id == payout.player_id, ErrorCode::InvalidSeeds);
+            // The player stays marked as minted for the epoch
+            state.total_minted = state.total_minted.saturating_sub(payout.player_reward);
+            state.lifetime_approvals =
+                state.lifetime_approvals.saturating_sub(payout.validators.len() as u64);
+
+            if game.emission_epoch == payout.epoch {
+                let validator_total: u64 = payout.validator_shares.iter().sum();
+                game.epoch_player_emission =
+                    game.epoch_player_emission.saturating_sub(payout.player_reward);
+                game.epoch_validator_emission =
+                    game.epoch_validator_emission.saturating_sub(validator_total);
+                game.epoch_creator_emission =
+                    game.epoch_creator_emission.saturating_sub(payout.creator_reward);
+            }
+        }
+    }
+
+    emit!(DisputeResolvedEvent {
+        game_number: game.game_number,
+        player_id,
+        player_name,
+        epoch,
+        ruling,
+        ruled_by,
+    });
+    Ok(())
+}
+
+/// Mint a released payout to the player, its approving validators and the game creator.
+fn pay_out(game: &mut Account<Game>, payout: PendingPayout, now: i64) -> Result<()> {
+    mint_tokens_for_player(game, &payout.player_name, payout.player_reward, now)?;
+    for (validator, share) in payout.validators.iter().zip(&payout.validator_shares) {
+        mint_tokens(game, validator, *share);
+    }
+    if payout.creator_reward > 0 {
+        let creator = game.creator;
+        mint_tokens(game, &creator, payout.creator_reward);
+    }
+
+    emit!(PayoutReleasedEvent {
+        game_number: game.game_number,
+        player_name: payout.player_name,
+        epoch: payout.epoch,
+        player_reward: payout.player_reward,
+        validator_reward: payout.validator_shares.iter().sum(),
+        creator_reward: payout.creator_reward,
+    });
+    Ok(())
+}
+
+fn apply_reward_change_delay(dapp: &mut DApp, delay: i64) -> Result<()> {
+    require!(delay >= 0, ErrorCode::InvalidTimestamp);
+    dapp.reward_change_delay = delay;
+    Ok(())
+}
+
+fn apply_max_players_per_authority(dapp: &mut DApp, max_players: u8) -> Result<()> {
+    require!(
+        max_players >= 1 && max_players as usize <= MAX_PLAYERS_PER_AUTHORITY,
+        ErrorCode::InvalidRange
+    );
+    dapp.max_players_per_authority = max_players;
+    Ok(())
+}
+
+fn add_dapp_owner(dapp: &mut DApp, new_owner: Pubkey) -> Result<()> {
+    require!((dapp.owners_count as usize) < MAX_DAPP_OWNERS, ErrorCode::OwnersFull);
+    require!(!dapp.is_owner(&new_owner), ErrorCode::AlreadyOwner);
+
+    let idx = dapp.owners_count as usize;
+    dapp.owners[idx] = new_owner;
+    dapp.owners_count += 1;
+    msg!("Added new owner: {}", new_owner);
+    Ok(())
+}
+
+/// Swap the last owner into the removed slot. At least `threshold` owners must remain.
+fn remove_dapp_owner(dapp: &mut DApp, target_owner: &Pubkey) -> Result<()> {
+    let idx = dapp.owners[..dapp.owners_count as usize]
+        .iter()
+        .position(|k| k == target_owner)
+        .ok_or(ErrorCode::OwnerNotFound)?;
+    require!(dapp.owners_count > dapp.threshold, ErrorCode::InvalidThreshold);
+
+    let last_idx = dapp.owners_count as usize - 1;
+    if idx != last_idx {
+        dapp.owners[idx] = dapp.owners[last_idx];
+    }
+    dapp.owners[last_idx] = Pubkey::default();
+    dapp.owners_count -= 1;
+    msg!("Removed owner: {}", target_owner);
+    Ok(())
+}
+
+/// Take the validator at `index` out of the game's dense index: the last entry moves
+/// into the vacated slot and the index account left over is closed to `rent_to`.
+/// Also drops the validator's `stake` from `game.total_stake`.
+fn vacate_validator_slot<'info>(
+    game: &mut Account<'info, Game>,
+    index: u32,
+    stake: u64,
+    validator_index: &mut Account<'info, ValidatorIndex>,
+    last_validator_index: Option<&Account<'info, ValidatorIndex>>,
+    moved_validator_pda: Option<&mut Account<'info, ValidatorPda>>,
+    rent_to: AccountInfo<'info>,
+) -> Result<()> {
+    let game_number = game.game_number;
+    let last = game.validator_count.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+
+    if index == last {
+        validator_index.close(rent_to)?;
+    } else {
+        let last_entry = last_validator_index.ok_or(ErrorCode::MissingValidatorIndex)?;
+        verify_validator_index(&last_entry.key(), last_entry, game_number, last)?;
+
+        let moved = moved_validator_pda.ok_or(ErrorCode::MissingValidatorIndex)?;
+        verify_validator_pda(&moved.key(), moved, game_number)?;
+        require_keys_eq!(moved.address, last_entry.validator, ErrorCode::MissingValidatorIndex);
+
+        validator_index.validator = last_entry.validator;
+        moved.index = index;
+        last_entry.close(rent_to)?;
+    }
+
+    game.total_stake = game.total_stake.checked_sub(stake).ok_or(ErrorCode::Overflow)?;
+    game.validator_count = last;
+    Ok(())
+}
+
+/// Re-derive a `ValidatorIndex` address for (game_number, index).
+fn verify_validator_index(key: &Pubkey, entry: &ValidatorIndex, game_number: u32, index: u32) -> Result<()> {
+    let derived = Pubkey::create_program_address(
+        &[
+            b"validator_index",
+            &game_number.to_le_bytes(),
+            &index.to_le_bytes(),
+            &[entry.bump],
+        ],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::InvalidSeeds)?;
+    require_keys_eq!(derived, *key, ErrorCode::InvalidSeeds);
+    Ok(())
+}
+
+/// Message a game server signs to attest that `player_name` was active in `epoch`
+/// of `game_number` with `activity_score`.
+///
+/// No validator is named on purpose: the attestation is a statement about the player,
+/// and every validator in the player's group relays the same one. Relaying only adds
+/// the relaying validator's own approval, so a copied attestation gains it nothing.
+pub fn attestation_message(game_number: u32, epoch: u64, player_name: &str, activity_score: u64) -> Vec<u8> {
+    let mut message = ATTESTATION_DOMAIN.to_vec();
+    message.extend_from_slice(&game_number.to_le_bytes());
+    message.extend_from_slice(&epoch.to_le_bytes());
+    message.extend_from_slice(&activity_score.to_le_bytes());
+    message.extend_from_slice(player_name.as_bytes());
+    message
+}
+
+/// (public key, message) of every signature checked by an ed25519 precompile instruction
+/// in this transaction. The runtime has verified them all by the time we run. Offsets
+/// must point into the precompile instruction itself.
+fn ed25519_signed_messages(instructions_info: &AccountInfo) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    // Ed25519SignatureOffsets: seven u16s per signature after a two-byte header
+    const HEADER_LEN: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const THIS_INSTRUCTION: u16 = u16::MAX;
+
+    let read_u16 = |data: &[u8], at: usize| -> Result<u16> {
+        let bytes = data.get(at..at + 2).ok_or(ErrorCode::InvalidAttestation)?;
+        Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+    };
+
+    let mut signed = Vec::new();
+    let mut index = 0;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_info) {
+        index += 1;
+        if ix.program_id != ed25519_program::ID {
+            continue;
+        }
+        let data = &ix.data;
+        let count = *data.first().ok_or(ErrorCode::InvalidAttestation)? as usize;
+        for i in 0..count {
+            let at = HEADER_LEN + i * OFFSETS_LEN;
+            let public_key_offset = read_u16(data, at + 4)? as usize;
+            let message_offset = read_u16(data, at + 8)? as usize;
+            let message_size = read_u16(data, at + 10)? as usize;
+            require!(
+                read_u16(data, at + 2)? == THIS_INSTRUCTION
+                    && read_u16(data, at + 6)? == THIS_INSTRUCTION
+                    && read_u16(data, at + 12)? == THIS_INSTRUCTION,
+                ErrorCode::InvalidAttestation
+            );
+
+            let key = data
+                .get(public_key_offset..public_key_offset + 32)
+                .ok_or(ErrorCode::InvalidAttestation)?;
+            let message = data
+                .get(message_offset..message_offset + message_size)
+                .ok_or(ErrorCode::InvalidAttestation)?;
+            signed.push((Pubkey::try_from(key).map_err(|_| ErrorCode::InvalidAttestation)?, message.to_vec()));
+        }
+    }
+    Ok(signed)
+}
+
+/// Commitment a validator posts in `punch_in` for the secret it reveals later.
+pub fn seed_commitment(secret: &[u8; 32], validator: &Pubkey) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(secret);
+    hasher.update(validator.to_bytes());
+    hasher.finalize().into()
 }
 
-// Pagination
-#[derive(Accounts)]
-pub struct GetPlayerListPdaPage<'info> {
-    #[account(seeds = [b"dapp"], bump)]
-    pub dapp: Account<'info, DApp>,
+/// Hash of the first slot at or after `target` in the SlotHashes sysvar, read without
+/// deserializing the whole list. Fails until such a slot exists; `None` once the
+/// sysvar no longer reaches back to `target`.
+fn slot_hash_at(slot_hashes_info: &AccountInfo, target: u64) -> Result<Option<[u8; 32]>> {
+    let data = slot_hashes_info.try_borrow_data()?;
+    // [u64 len][u64 slot][32-byte hash]..., newest first
+    require!(data.len() >= 8, ErrorCode::HashConversionError);
+    let len = u64::from_le_bytes(data[..8].try_into().map_err(|_| ErrorCode::HashConversionError)?);
+
+    let mut found = None;
+    let mut reaches_target = false;
+    for entry in data[8..].chunks_exact(40).take(len as usize) {
+        let slot = u64::from_le_bytes(entry[..8].try_into().map_err(|_| ErrorCode::HashConversionError)?);
+        if slot < target {
+            reaches_target = true;
+            break;
+        }
+        found = Some(&entry[8..]);
+        if slot == target {
+            reaches_target = true;
+            break;
+        }
+    }
+    let hash = found.ok_or(ErrorCode::SeedSlotPending)?;
+    if !reaches_target {
+        return Ok(None);
+    }
+    Ok(Some(hash.try_into().map_err(|_| ErrorCode::HashConversionError)?))
 }
-#[derive(Accounts)]
-#[instruction(game_number: u32)]
-pub struct GetValidatorListPdaPage<'info> {
-    #[account(seeds = [b"game", &game_number.to_le_bytes()], bump)]
-    pub game: Account<'info, Game>,
+
+/// Mix the revealed secrets with the hash of the round's target slot into the epoch seed.
+fn finalize_epoch_seed(game: &mut Game, epoch: u64, slot_hash: &[u8; 32]) -> Result<()> {
+    let mut hasher = Keccak256::new();
+    hasher.update(game.seed_accumulator);
+    hasher.update(slot_hash);
+    hasher.update(epoch.to_le_bytes());
+    let hash_res = hasher.finalize();
+    let seed = u64::from_le_bytes(
+        hash_res[0..8].try_into().map_err(|_| ErrorCode::HashConversionError)?
+    );
+    game.last_seed = Some(seed);
+
+    emit!(SeedFinalizedEvent {
+        game_number: game.game_number,
+        epoch,
+        seed,
+        commit_count: game.commit_count,
+        reveal_count: game.reveal_count,
+        seed_slot: game.seed_slot,
+    });
+    Ok(())
 }
 
-// ------------------------------------------------------------------
-// Errors & Utility
-// ------------------------------------------------------------------
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Unauthorized.")]
-    Unauthorized,
-    #[msg("Not in punch-in period.")]
-    NotInPunchInPeriod,
-    #[msg("Not in mint period.")]
-    NotInMintPeriod,
-    #[msg("Insufficient stake.")]
-    InsufficientStake,
-    #[msg("Player name already exists.")]
-    PlayerNameExists,
-    #[msg("Validator not registered.")]
-    ValidatorNotRegistered,
-    #[msg("Hash conversion error.")]
-    HashConversionError,
-    #[msg("Invalid timestamp.")]
-    InvalidTimestamp,
-    #[msg("Game number mismatch.")]
-    GameNumberMismatch,
-    #[msg("Game status already set.")]
-    GameStatusAlreadySet,
-    #[msg("Game is blacklisted.")]
-    GameIsBlacklisted,
-    #[msg("Game not whitelisted.")]
-    GameNotWhitelisted,
-    #[msg("Name cooldown active.")]
-    NameChangeCooldown,
-    #[msg("Invalid seeds.")]
-    InvalidSeeds,
-    #[msg("Invalid range.")]
-    InvalidRange,
-    #[msg("No seed generated.")]
-    NoSeed,
-    #[msg("Account already expanded.")]
-    AlreadyExpanded,
+fn slashing_epoch(timestamp: i64) -> i64 {
+    timestamp.div_euclid(SLASHING_EPOCH_SECONDS)
 }
 
-fn calculate_failover_tolerance(total_validators: usize) -> usize {
-    let total_groups = (total_validators + 3) / 4;
-    let num_digits = total_groups.to_string().len();
-    num_digits + 1
+/// When `validator` approved a pending agreement for `player_id` in `game`.
+fn find_approval(game: &Game, player_id: u64, validator: &Pubkey) -> Option<i64> {
+    game.minting_agreements
+        .iter()
+        .filter(|ma| ma.player_id == player_id)
+        .find_map(|ma| {
+            let position = ma.validators.iter().position(|v| v == validator)?;
+            ma.approved_at.get(position).copied()
+        })
 }
 
-fn calculate_group_id(address: &Pubkey, seed: u64) -> Result<u64> {
-    let mut hasher = Keccak256::new();
-    hasher.update(address.to_bytes());
-    hasher.update(seed.to_le_bytes());
-    let result = hasher.finalize();
-    let bytes: [u8; 8] = result[0..8]
-        .try_into()
-        .map_err(|_| ErrorCode::HashConversionError)?;
-    Ok(u64::from_be_bytes(bytes))
+/// Take `SLASH_BPS` of the validator's stake out of its PDA, paying the reporter
+/// their share and burning the rest. Returns (slashed, reporter_reward).
+fn slash_stake<'info>(
+    val_pda: &mut Account<'info, ValidatorPda>,
+    reporter: &AccountInfo<'info>,
+    incinerator: &AccountInfo<'info>,
+) -> Result<(u64, u64)> {
+    let slashed = (val_pda.stake as u128 * SLASH_BPS as u128 / 10_000) as u64;
+    let reporter_reward = (slashed as u128 * REPORTER_REWARD_BPS as u128 / 10_000) as u64;
+    let burned = slashed - reporter_reward;
+
+    val_pda.stake -= slashed;
+    val_pda.to_account_info().sub_lamports(slashed)?;
+    reporter.add_lamports(reporter_reward)?;
+    incinerator.add_lamports(burned)?;
+    Ok((slashed, reporter_reward))
 }
 
 // Optionally "expand" the account if needed (commented out):
@@ -705,7 +4592,7 @@ fn calculate_group_id(address: &Pubkey, seed: u64) -> Result<u64> {
 //     Ok(())
 // }
 
-fn mint_tokens_for_player(_game: &mut Account<Game>, _player_name: &str, _current_time: i64) -> Result<()> {
+fn mint_tokens_for_player(_game: &mut Account<Game>, _player_name: &str, _amount: u64, _current_time: i64) -> Result<()> {
     // no-op
     Ok(())
 }
@@ -713,17 +4600,452 @@ fn mint_tokens(_game: &mut Account<Game>, _address: &Pubkey, _amount: u64) {
     // no-op or do some logic
 }
 
-/// For a custom "validator info" retrieval if desired
-// pub fn get_validator_info(ctx: Context<GetValidatorList>, validator: Pubkey) -> Result<()> {
-//     let game = &ctx.accounts.game;
-//     let seed = game.last_seed.ok_or(ErrorCode::HashConversionError)?;
-//     let group_id = calculate_group_id(&validator, seed)?;
-//     emit!(ValidatorInfoEvent { seed, group_id });
-//     Ok(())
-// }
-
 #[event]
 pub struct ValidatorInfoEvent {
+    pub game_number: u32,
+    pub validator: Pubkey,
     pub seed: u64,
     pub group_id: u64,
+    pub failover_tolerance: u64,
+}
+
+#[event]
+pub struct SeedSlotFixedEvent {
+    pub game_number: u32,
+    pub epoch: u64,
+    pub seed_slot: u64,
+    /// The previous seed slot, if it aged out of SlotHashes before the seed was finalized
+    pub expired_slot: Option<u64>,
+}
+
+#[event]
+pub struct SeedFinalizedEvent {
+    pub game_number: u32,
+    pub epoch: u64,
+    pub seed: u64,
+    pub commit_count: u32,
+    pub reveal_count: u32,
+    pub seed_slot: u64,
+}
+
+#[event]
+pub struct RewardAddressChangeRequestedEvent {
+    pub player_index: u32,
+    pub current_reward_address: Pubkey,
+    pub pending_reward_address: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct RewardAddressChangeCancelledEvent {
+    pub player_index: u32,
+    pub cancelled_reward_address: Pubkey,
+}
+
+#[event]
+pub struct RewardAddressChangeFinalizedEvent {
+    pub player_index: u32,
+    pub old_reward_address: Pubkey,
+    pub new_reward_address: Pubkey,
+}
+
+#[event]
+pub struct PlayerDeregisteredEvent {
+    pub player_index: u32,
+    pub player_id: u64,
+    pub authority: Pubkey,
+    /// Index of the player moved into `player_index`, if any
+    pub moved_from: Option<u32>,
+}
+
+#[event]
+pub struct PlayerJoinedGameEvent {
+    pub game_number: u32,
+    pub player_index: u32,
+    pub player_id: u64,
+}
+
+#[event]
+pub struct PlayerLeftGameEvent {
+    pub game_number: u32,
+    pub player_index: u32,
+    pub player_id: u64,
+}
+
+#[event]
+pub struct PlayerAuthorityChangedEvent {
+    pub player_index: u32,
+    pub old_authority: Pubkey,
+    pub new_authority: Pubkey,
+}
+
+#[event]
+pub struct GameStatusChangedEvent {
+    pub game_number: u32,
+    pub old_status: GameStatus,
+    pub new_status: GameStatus,
+    pub reason: String,
+    pub changed_by: Pubkey,
+}
+
+#[event]
+pub struct GameServerRegisteredEvent {
+    pub game_number: u32,
+    pub signing_key: Pubkey,
+}
+
+#[event]
+pub struct GameServerRemovedEvent {
+    pub game_number: u32,
+    pub signing_key: Pubkey,
+}
+
+#[event]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ProposalApprovedEvent {
+    pub proposal_id: u64,
+    pub owner: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecutedEvent {
+    pub proposal_id: u64,
+    pub executor: Pubkey,
+    pub action: ProposalAction,
+}
+
+#[event]
+pub struct ProposalCancelledEvent {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+    pub cancelled_by: Pubkey,
+    /// Cleared after expiring rather than withdrawn by the proposer
+    pub expired: bool,
+}
+
+#[event]
+pub struct GameRetiredEvent {
+    pub game_number: u32,
+    pub old_status: GameStatus,
+    pub dropped_agreements: u32,
+    pub validator_count: u32,
+    pub reason: String,
+    pub retired_by: Pubkey,
+}
+
+#[event]
+pub struct ValidatorWithdrawnEvent {
+    pub game_number: u32,
+    pub validator: Pubkey,
+    pub index: u32,
+    pub withdrawn_stake: u64,
+    pub validator_count: u32,
+}
+
+#[event]
+pub struct ValidatorEvictedEvent {
+    pub game_number: u32,
+    pub validator: Pubkey,
+    pub index: u32,
+    pub cranker: Pubkey,
+    pub refunded_stake: u64,
+    pub validator_count: u32,
+}
+
+#[event]
+pub struct ValidatorStakedEvent {
+    pub game_number: u32,
+    pub validator: Pubkey,
+    pub amount: u64,
+    pub stake: u64,
+    pub total_stake: u64,
+}
+
+#[event]
+pub struct GroupFailureEvent {
+    pub game_number: u32,
+    pub validator: Pubkey,
+    pub player_id: u64,
+    pub player_name: String,
+    pub seed: u64,
+    pub distance: u64,
+    pub failover_tolerance: u64,
+    pub failed_approvals: u32,
+}
+
+#[event]
+pub struct ConflictingApprovalEvidence {
+    pub validator: Pubkey,
+    pub player_id: u64,
+    pub game_a: u32,
+    pub game_b: u32,
+    pub approved_at_a: i64,
+    pub approved_at_b: i64,
+    pub epoch: i64,
+}
+
+#[event]
+pub struct RepeatedGroupFailureEvidence {
+    pub validator: Pubkey,
+    pub failed_approvals: u32,
+}
+
+#[event]
+pub struct ValidatorSlashedEvent {
+    pub validator: Pubkey,
+    pub reporter: Pubkey,
+    pub evidence: SlashEvidence,
+    pub slashed: u64,
+    pub reporter_reward: u64,
+    pub burned: u64,
+    pub remaining_stake: u64,
+}
+
+#[event]
+pub struct PlayerBannedEvent {
+    pub game_number: u32,
+    pub player_index: u32,
+    pub reason_code: u16,
+    pub expires_at: Option<i64>,
+    pub banned_by: Pubkey,
+}
+
+#[event]
+pub struct PlayerUnbannedEvent {
+    pub game_number: u32,
+    pub player_index: u32,
+    pub unbanned_by: Pubkey,
+}
+
+#[event]
+pub struct BanAppealedEvent {
+    pub game_number: u32,
+    pub player_index: u32,
+    pub authority: Pubkey,
+    pub message: String,
+}
+
+#[event]
+pub struct BanAppealDismissedEvent {
+    pub game_number: u32,
+    pub player_index: u32,
+    pub dismissed_by: Pubkey,
+}
+
+#[event]
+pub struct AgreementExpiredEvent {
+    pub game_number: u32,
+    pub player_name: String,
+    pub created_epoch: u64,
+    pub approvals: u32,
+    pub pruned_epoch: u64,
+}
+
+#[event]
+pub struct PayoutQueuedEvent {
+    pub game_number: u32,
+    pub player_name: String,
+    pub epoch: u64,
+    pub release_at: i64,
+}
+
+#[event]
+pub struct PayoutReleasedEvent {
+    pub game_number: u32,
+    pub player_name: String,
+    pub epoch: u64,
+    pub player_reward: u64,
+    pub validator_reward: u64,
+    pub creator_reward: u64,
+}
+
+#[event]
+pub struct PayoutDisputedEvent {
+    pub game_number: u32,
+    pub player_id: u64,
+    pub player_name: String,
+    pub epoch: u64,
+    pub disputer: Pubkey,
+    pub evidence: String,
+}
+
+#[event]
+pub struct DisputeResolvedEvent {
+    pub game_number: u32,
+    pub player_id: u64,
+    pub player_name: String,
+    pub epoch: u64,
+    pub ruling: DisputeRuling,
+    pub ruled_by: Pubkey,
+}
+
+// ------------------------------------------------------------------
+// Off-chain simulation of grouping strategies
+// ------------------------------------------------------------------
+
+/// Monte Carlo harness for comparing `GroupingStrategy` choices before setting one on a game.
+/// Not built for the on-chain target.
+#[cfg(not(target_os = "solana"))]
+pub mod simulation {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct SimulationParams {
+        pub validator_count: u32,
+        /// The first `colluders` validators of each trial work together
+        pub colluders: u32,
+        /// Same-group approvals an agreement needs (at least 2)
+        pub quorum: u32,
+        pub trials: u32,
+        /// Makes runs reproducible
+        pub rng_seed: u64,
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct SimulationReport {
+        pub strategy: GroupingStrategy,
+        /// Share of trials where the colluders alone could resolve the player's agreement
+        pub collusion_probability: f64,
+        /// Share of trials where the player's group held enough honest validators
+        pub honest_success_rate: f64,
+    }
+
+    /// Run `params.trials` random games under `strategy`. Each trial draws fresh validator
+    /// keys, a player and a seed, then applies the same player-group rule as
+    /// `submit_minting_list`.
+    pub fn simulate(strategy: GroupingStrategy, params: SimulationParams) -> Result<SimulationReport> {
+        require!(
+            params.quorum >= 2 && params.colluders <= params.validator_count && params.trials > 0,
+            ErrorCode::InvalidRange
+        );
+        let mut rng = XorShift64(params.rng_seed.max(1));
+        let tolerance = strategy.tolerance(params.validator_count);
+        let colluders = params.colluders as usize;
+
+        let mut collusion_trials = 0u64;
+        let mut honest_successes = 0u64;
+        for _ in 0..params.trials {
+            let seed = rng.next();
+            let mut positions = Vec::with_capacity(params.validator_count as usize);
+            for _ in 0..params.validator_count {
+                let mut key = [0u8; 32];
+                for chunk in key.chunks_mut(8) {
+                    chunk.copy_from_slice(&rng.next().to_le_bytes());
+                }
+                positions.push(strategy.group_of(&Pubkey::new_from_array(key), seed, params.validator_count)?);
+            }
+
+            let player = strategy.group_of(&player_group_key(rng.next()), seed, params.validator_count)?;
+
+            // members of `range` allowed to approve the player
+            let approvers = |range: std::ops::Range<usize>| {
+                positions[range]
+                    .iter()
+                    .filter(|p| strategy.distance(player, **p, params.validator_count) <= tolerance)
+                    .count() as u32
+            };
+
+            if approvers(0..colluders) >= params.quorum {
+                collusion_trials += 1;
+            }
+            if approvers(colluders..positions.len()) >= params.quorum {
+                honest_successes += 1;
+            }
+        }
+
+        Ok(SimulationReport {
+            strategy,
+            collusion_probability: collusion_trials as f64 / params.trials as f64,
+            honest_success_rate: honest_successes as f64 / params.trials as f64,
+        })
+    }
+
+    /// `simulate` each strategy with the same parameters, for side-by-side comparison.
+    pub fn compare(strategies: &[GroupingStrategy], params: SimulationParams) -> Result<Vec<SimulationReport>> {
+        strategies.iter().map(|s| simulate(*s, params)).collect()
+    }
+
+    struct XorShift64(u64);
+    impl XorShift64 {
+        fn next(&mut self) -> u64 {
+            let mut x = self.0;
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            self.0 = x;
+            x
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const VALIDATORS: u32 = 40;
+
+        fn run(strategy: GroupingStrategy, colluders: u32) -> SimulationReport {
+            let params = SimulationParams {
+                validator_count: VALIDATORS,
+                colluders,
+                quorum: 2,
+                trials: 1_000,
+                rng_seed: 7,
+            };
+            simulate(strategy, params).unwrap()
+        }
+
+        #[test]
+        fn legacy_groups_never_match() {
+            for colluders in [0, 4, 20] {
+                let report = run(GroupingStrategy::Legacy, colluders);
+                assert_eq!(report.collusion_probability, 0.0);
+                assert_eq!(report.honest_success_rate, 0.0);
+            }
+        }
+
+        #[test]
+        fn bucketed_collusion_matches_binomial_bound() {
+            // 2 of 4 colluders land in the player's bucket out of 10 with probability
+            // 1 - 0.9^4 - 4 * 0.1 * 0.9^3
+            let report = run(GroupingStrategy::Bucketed { group_size: 4 }, 4);
+            assert!((report.collusion_probability - 0.052).abs() < 0.03);
+            assert!(report.honest_success_rate > 0.8);
+        }
+
+        #[test]
+        fn ring_keeps_honest_validators_agreeing() {
+            // 5 of 40 slots are within radius 2 of the player
+            let report = run(GroupingStrategy::Ring { radius: 2 }, 4);
+            assert!(report.collusion_probability < 0.15);
+            assert!(report.honest_success_rate > 0.9);
+        }
+
+        #[test]
+        fn more_colluders_never_help_honest_validators() {
+            for strategy in [GroupingStrategy::Bucketed { group_size: 4 }, GroupingStrategy::Ring { radius: 2 }] {
+                let none = run(strategy, 0);
+                let few = run(strategy, 4);
+                let many = run(strategy, 20);
+                assert_eq!(none.collusion_probability, 0.0);
+                assert!(few.collusion_probability <= many.collusion_probability);
+                assert!(many.collusion_probability > 0.5);
+                assert!(none.honest_success_rate >= many.honest_success_rate);
+            }
+        }
+
+        #[test]
+        fn rejects_invalid_params() {
+            let params = SimulationParams { validator_count: 4, colluders: 5, quorum: 2, trials: 1, rng_seed: 1 };
+            assert!(simulate(GroupingStrategy::Ring { radius: 1 }, params).is_err());
+            let params = SimulationParams { quorum: 1, colluders: 0, ..params };
+            assert!(simulate(GroupingStrategy::Ring { radius: 1 }, params).is_err());
+        }
+    }
 }
@@ -4,9 +4,12 @@ from enum import IntEnum
 import traceback
 import json
 import os
+# pycryptodome, for the keccak seed commitment
+from Crypto.Hash import keccak
 # anchorpy
 from anchorpy import Program, Provider, Wallet, Idl, Context
 from anchorpy.program.namespace.instruction import AccountMeta
+from solders.instruction import Instruction
 
 # solana / solders
 from solders.keypair import Keypair
@@ -19,6 +22,11 @@ from solana.rpc.core import RPCException
 # Constants
 SPL_TOKEN_PROGRAM_ID = Pubkey.from_string("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA")
 ASSOCIATED_TOKEN_PROGRAM_ID = Pubkey.from_string("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL")
+ED25519_PROGRAM_ID = Pubkey.from_string("Ed25519SigVerify111111111111111111111111111")
+SYSVAR_INSTRUCTIONS_ID = Pubkey.from_string("Sysvar1nstructions1111111111111111111111111")
+SYSVAR_CLOCK_ID = Pubkey.from_string("SysvarC1ock11111111111111111111111111111111")
+SYSVAR_SLOT_HASHES_ID = Pubkey.from_string("SysvarS1otHashes111111111111111111111111111")
+MIN_VALIDATOR_STAKE = 1_000_000_000
 
 class GameStatus(IntEnum):
     Probationary = 0
@@ -126,6 +134,7 @@ async def initialize_game(
             tx = await program.rpc["initialize_game"](
                 game_number,
                 description,
+                program.provider.wallet.public_key,  # creator
                 ctx=Context(
                     accounts={
                         "game": game_pda,
@@ -152,34 +161,31 @@ async def register_validator_pda(
     game_pda: Pubkey,
     game_number: int,
     validator_kp: Keypair,
-    fancy_mint: Pubkey,           # Pass the mint pubkey
-    dapp_pda: Pubkey,             # Pass the DApp PDA
 ) -> Pubkey:
-    """Create a new validator + ATA for that validator on chain."""
+    """Create a new validator on chain, or reuse the one already registered."""
     print("\nRegistering a new Validator PDA (user-based seeds)...")
     
     # Derive the validator_pda
     seeds_val = [b"validator", game_number.to_bytes(4, "little"), bytes(validator_kp.pubkey())]
     validator_pda, _ = Pubkey.find_program_address(seeds_val, program.program_id)
     print(f"[DEBUG] Derived validator_pda = {validator_pda}")
+    if (await client.get_account_info(validator_pda, commitment=Confirmed)).value is not None:
+        print("ValidatorPda already exists. Skipping registration.")
+        return validator_pda
 
-    # Derive the validator's ATA
-    validator_ata_pubkey = find_associated_token_address(validator_kp.pubkey(), fancy_mint)
-    print(f"[DEBUG] Derived validator ATA: {validator_ata_pubkey}")
+    # Secondary index => [b"validator_index", game_number, game.validator_count]
+    game_data = await program.account["Game"].fetch(game_pda)
+    seeds_idx = [b"validator_index", game_number.to_bytes(4, "little"), game_data.validator_count.to_bytes(4, "little")]
+    validator_index_pda, _ = Pubkey.find_program_address(seeds_idx, program.program_id)
+    print(f"[DEBUG] Derived validator_index = {validator_index_pda}")
 
-    # Build AnchorPy Context with the derived ATA
     ctx = Context(
         accounts={
             "game": game_pda,
-            "fancy_mint": fancy_mint,
             "validator_pda": validator_pda,
+            "validator_index": validator_index_pda,
             "user": validator_kp.pubkey(),
-            "validator_ata": validator_ata_pubkey,  # Pass the ATA pubkey
-            "dapp": dapp_pda,
-            "token_program": SPL_TOKEN_PROGRAM_ID,
-            "associated_token_program": ASSOCIATED_TOKEN_PROGRAM_ID,
             "system_program": SYS_PROGRAM_ID,
-            "rent": Pubkey.from_string("SysvarRent111111111111111111111111111111111")
         },
         signers=[validator_kp],
     )
@@ -198,12 +204,88 @@ async def register_validator_pda(
 
     return validator_pda
 
-async def punch_in(program: Program, game_pda: Pubkey, game_number: int, validator_kp: Keypair, validator_pda: Pubkey):
-    """Punch in as validator for the given game."""
+async def stake_validator(program: Program, game_pda: Pubkey, validator_kp: Keypair, validator_pda: Pubkey, amount: int):
+    """Stake lamports into the ValidatorPda; minting lists need MIN_VALIDATOR_STAKE."""
+    print("\nStaking the Validator...")
+    validator_data = await program.account["ValidatorPda"].fetch(validator_pda)
+    if validator_data.stake >= amount:
+        print(f"Already staked {validator_data.stake} lamports. Skipping.")
+        return
+    try:
+        tx = await program.rpc["stake_validator"](
+            amount - validator_data.stake,
+            ctx=Context(
+                accounts={
+                    "validator_pda": validator_pda,
+                    "game": game_pda,
+                    "user": validator_kp.pubkey(),
+                    "system_program": SYS_PROGRAM_ID,
+                },
+                signers=[validator_kp],
+            )
+        )
+        print(f"Validator staked. Transaction Signature: {tx}")
+    except RPCException as e:
+        print(f"Error staking validator: {e}")
+        traceback.print_exc()
+        raise
+
+async def configure_game_epochs(program: Program, dapp_pda: Pubkey, game_pda: Pubkey, game_number: int,
+                                epoch_length: int, punch_in_length: int, reveal_length: int):
+    """Shorten the game's epochs so one run can go through a whole seed round.
+    This starts a fresh epoch, so the punch-in window opens right away."""
+    print("\nConfiguring game epochs...")
+    try:
+        tx = await program.rpc["configure_game_epochs"](
+            game_number,
+            epoch_length,
+            punch_in_length,
+            reveal_length,
+            ctx=Context(
+                accounts={
+                    "game": game_pda,
+                    "dapp": dapp_pda,
+                    "signer": program.provider.wallet.public_key,
+                },
+            )
+        )
+        print(f"Epochs configured. Transaction Signature: {tx}")
+    except RPCException as e:
+        print(f"Error configuring epochs: {e}")
+        traceback.print_exc()
+        raise
+
+async def cluster_time(client: AsyncClient) -> int:
+    """unix_timestamp of the cluster's Clock sysvar."""
+    clock = (await client.get_account_info(SYSVAR_CLOCK_ID)).value.data
+    return int.from_bytes(clock[32:40], "little", signed=True)
+
+async def wait_for_epoch_offset(program: Program, client: AsyncClient, game_pda: Pubkey, offset: int):
+    """Sleep until the cluster clock is `offset` seconds into the game's current epoch."""
+    game_data = await program.account["Game"].fetch(game_pda)
+    while True:
+        elapsed = (await cluster_time(client) - game_data.epoch_start) % game_data.epoch_length
+        if elapsed >= offset:
+            return
+        print(f"Waiting {offset - elapsed}s for the next window...")
+        await asyncio.sleep(min(offset - elapsed, 5))
+
+def seed_commitment(secret: bytes, validator: Pubkey) -> bytes:
+    """Same as the program's seed_commitment: keccak(secret || validator)."""
+    return keccak.new(digest_bits=256, data=secret + bytes(validator)).digest()
+
+async def punch_in(program: Program, game_pda: Pubkey, game_number: int, validator_kp: Keypair, validator_pda: Pubkey) -> bytes:
+    """
+    Punch in as validator for the given game, committing to a fresh random secret.
+    Returns the secret, which reveal_seed needs during the reveal window.
+    """
     print("\nPunching In as Validator...")
+    secret = os.urandom(32)
+    commitment = seed_commitment(secret, validator_kp.pubkey())
     try:
         tx = await program.rpc["punch_in"](
             game_number,
+            list(commitment),
             ctx=Context(
                 accounts={
                     "game": game_pda,
@@ -219,6 +301,59 @@ async def punch_in(program: Program, game_pda: Pubkey, game_number: int, validat
         print(f"Error punching in: {e}")
         traceback.print_exc()
         raise
+    return secret
+
+async def reveal_seed(program: Program, game_pda: Pubkey, game_number: int, validator_kp: Keypair,
+                      validator_pda: Pubkey, secret: bytes):
+    """Reveal the secret committed in punch_in, during the reveal window."""
+    print("\nRevealing the seed secret...")
+    try:
+        tx = await program.rpc["reveal_seed"](
+            game_number,
+            list(secret),
+            ctx=Context(
+                accounts={
+                    "game": game_pda,
+                    "validator_pda": validator_pda,
+                    "validator": validator_kp.pubkey(),
+                },
+                signers=[validator_kp],
+            )
+        )
+        print(f"Secret revealed. Transaction Signature: {tx}")
+    except RPCException as e:
+        print(f"Error revealing seed: {e}")
+        traceback.print_exc()
+        raise
+
+async def finalize_seed(program: Program, game_pda: Pubkey, game_number: int):
+    """
+    Crank finalize_seed once the reveal window is over. The first call only fixes the
+    seed slot, so keep calling until the slot has passed and the seed is set.
+    """
+    print("\nFinalizing the epoch seed...")
+    while True:
+        try:
+            tx = await program.rpc["finalize_seed"](
+                game_number,
+                ctx=Context(
+                    accounts={
+                        "game": game_pda,
+                        "slot_hashes": SYSVAR_SLOT_HASHES_ID,
+                    },
+                )
+            )
+            print(f"finalize_seed => Transaction Signature: {tx}")
+        except RPCException as e:
+            if "SeedSlotPending" not in str(e):
+                print(f"Error finalizing seed: {e}")
+                traceback.print_exc()
+                raise
+        game_data = await program.account["Game"].fetch(game_pda)
+        if game_data.last_seed is not None:
+            print(f"Seed finalized: {game_data.last_seed}")
+            return
+        await asyncio.sleep(1)
 
 async def register_player_pda(
     program: Program,
@@ -247,20 +382,32 @@ async def register_player_pda(
     user_ata = find_associated_token_address(user_pubkey, fancy_mint)
     print(f"[DEBUG] Derived user_ata = {user_ata}")
 
+    # Reverse lookup => [b"player_authority", authority]
+    authority_index, _ = Pubkey.find_program_address(
+        [b"player_authority", bytes(user_pubkey)],
+        program.program_id
+    )
+    print(f"[DEBUG] Derived authority_index = {authority_index}")
+
+    # Names are unique => [b"player_name", name]
+    player_name_pda, _ = Pubkey.find_program_address(
+        [b"player_name", name.encode()],
+        program.program_id
+    )
+
     try:
         tx_sig = await program.rpc["register_player_pda"](
             name,
+            user_ata,                                   # reward_address
             ctx=Context(
                 accounts={
                     "dapp": dapp_pda,
-                    "fancy_mint": fancy_mint,               # Pass fancy_mint
                     "player_pda": player_pda,
+                    "authority_index": authority_index,
+                    "player_name": player_name_pda,
+                    "authority": user_pubkey,           # Must sign
                     "user": user_pubkey,
-                    "user_ata": user_ata,                   # Pass user_ata
-                    "token_program": SPL_TOKEN_PROGRAM_ID,
-                    "associated_token_program": ASSOCIATED_TOKEN_PROGRAM_ID,
                     "system_program": SYS_PROGRAM_ID,
-                    "rent": Pubkey.from_string("SysvarRent111111111111111111111111111111111"),
                 },
                 signers=[program.provider.wallet.payer],
             )
@@ -271,61 +418,171 @@ async def register_player_pda(
         traceback.print_exc()
         raise
 
+def attestation_message(game_number: int, epoch: int, player_name: str, activity_score: int) -> bytes:
+    """Same as the program's attestation_message."""
+    return (
+        b"fancoin-attestation"
+        + game_number.to_bytes(4, "little")
+        + epoch.to_bytes(8, "little")
+        + activity_score.to_bytes(8, "little")
+        + player_name.encode()
+    )
+
+def ed25519_attestations(signed: list) -> Instruction:
+    """
+    One ed25519 precompile instruction checking every (server key, signature, message).
+    All offsets point into this instruction (index u16::MAX), as submit_minting_list requires.
+    """
+    this_instruction = 0xFFFF
+    payload_start = 2 + 14 * len(signed)
+    offsets = bytearray([len(signed), 0])
+    payload = bytearray()
+    for key, signature, message in signed:
+        key_offset = payload_start + len(payload)
+        payload += key
+        signature_offset = payload_start + len(payload)
+        payload += signature
+        message_offset = payload_start + len(payload)
+        payload += message
+        for value in (signature_offset, this_instruction, key_offset, this_instruction,
+                      message_offset, len(message), this_instruction):
+            offsets += value.to_bytes(2, "little")
+    return Instruction(ED25519_PROGRAM_ID, bytes(offsets + payload), [])
+
+async def current_epoch(program: Program, client: AsyncClient, game_pda: Pubkey) -> int:
+    """The game's epoch at the cluster's clock, as the program's Game::epoch_at."""
+    game_data = await program.account["Game"].fetch(game_pda)
+    now = await cluster_time(client)
+    return game_data.epoch_base + (now - game_data.epoch_start) // game_data.epoch_length
+
+async def register_game_server(program: Program, dapp_pda: Pubkey, game_pda: Pubkey, game_number: int, server_kp: Keypair):
+    """Create the game's metadata and register server_kp as a trusted attestation signer."""
+    print("\nRegistering a game server...")
+    metadata_pda, _ = Pubkey.find_program_address(
+        [b"game_metadata", game_number.to_bytes(4, "little")],
+        program.program_id
+    )
+    wallet_pubkey = program.provider.wallet.public_key
+    try:
+        await program.rpc["set_game_metadata"](
+            game_number,
+            "Minimal Game",
+            "",
+            ctx=Context(
+                accounts={
+                    "game": game_pda,
+                    "dapp": dapp_pda,
+                    "metadata": metadata_pda,
+                    "signer": wallet_pubkey,
+                    "system_program": SYS_PROGRAM_ID,
+                },
+            )
+        )
+        tx = await program.rpc["add_game_server"](
+            game_number,
+            "http://localhost:8080",
+            server_kp.pubkey(),
+            ctx=Context(
+                accounts={
+                    "game": game_pda,
+                    "dapp": dapp_pda,
+                    "metadata": metadata_pda,
+                    "signer": wallet_pubkey,
+                },
+            )
+        )
+        print(f"Game server {server_kp.pubkey()} registered. Tx: {tx}")
+    except RPCException as e:
+        print(f"Error registering game server: {e}")
+        traceback.print_exc()
+        raise
+    return metadata_pda
+
+async def init_player_game_state(program: Program, game_pda: Pubkey, game_number: int, player_index: int) -> Pubkey:
+    """Create the player's per-game state, keyed by its stable player id."""
+    print("\nCreating the PlayerGameState...")
+    player_pda, _ = Pubkey.find_program_address(
+        [b"player_pda", player_index.to_bytes(4, "little")],
+        program.program_id
+    )
+    player_data = await program.account["PlayerPda"].fetch(player_pda)
+    state_pda, _ = Pubkey.find_program_address(
+        [b"player_game", game_number.to_bytes(4, "little"), player_data.id.to_bytes(8, "little")],
+        program.program_id
+    )
+    try:
+        tx = await program.rpc["init_player_game_state"](
+            game_number,
+            player_index,
+            ctx=Context(
+                accounts={
+                    "game": game_pda,
+                    "player_pda": player_pda,
+                    "player_game_state": state_pda,
+                    "user": program.provider.wallet.public_key,
+                    "system_program": SYS_PROGRAM_ID,
+                },
+            )
+        )
+        print(f"PlayerGameState created => {state_pda}. Tx: {tx}")
+    except RPCException as e:
+        print(f"Error creating PlayerGameState: {e}")
+        traceback.print_exc()
+        raise
+    return state_pda
+
 async def submit_minting_list(
     program: Program,
-    dapp_pda: Pubkey,
     game_pda: Pubkey,
     game_number: int,
+    epoch: int,
+    metadata_pda: Pubkey,
     validator_kp: Keypair,
     validator_pda: Pubkey,
-    fancy_mint: Pubkey,
-    mint_auth_pda: Pubkey,
-    leftover_player_pda: Pubkey,
-    leftover_player_ata: Pubkey,
+    server_kp: Keypair,
+    players: list,
 ):
     """
-    Example usage: single leftover (PlayerPda, ATA) => one "player_id".
-    The on-chain code expects leftover=2 per player => [PlayerPda, ATA].
-    
-    NOTE: Mark the PlayerPda leftover as `is_writable=True` so we can update it on-chain.
-    """
-    print("\nSubmitting Minting List => leftover [PlayerPda, ATA]...")
-
-    # leftover => 2 per player
-    leftover_accounts = [
-        # IMPORTANT: Mark the PlayerPda as is_writable=True
-        AccountMeta(pubkey=leftover_player_pda, is_signer=False, is_writable=True),
-        # The ATA also needs to be writable if we do a `mint_to` on it
-        AccountMeta(pubkey=leftover_player_ata, is_signer=False, is_writable=True),
-    ]
+    Submit players as (player_name, activity_score, PlayerPda, PlayerGameState), each with
+    an attestation signed by server_kp for this epoch. Only accepted once the epoch's seed
+    is finalized. Raises if the program rejects the list.
 
-    # We'll pass a single "player_id"
-    player_ids = [1]  # example
+    remaining_accounts => the validator's ValidatorPda, then each player's PlayerPda and
+    writable PlayerGameState. The ed25519 precompile instruction goes right before.
+    """
+    print("\nSubmitting Minting List with signed attestations...")
+
+    attested = []
+    signed = []
+    remaining_accounts = [AccountMeta(pubkey=validator_pda, is_signer=False, is_writable=True)]
+    for player_name, activity_score, player_pda, state_pda in players:
+        attested.append(program.type["AttestedPlayer"](player_name=player_name, activity_score=activity_score))
+        message = attestation_message(game_number, epoch, player_name, activity_score)
+        signed.append((bytes(server_kp.pubkey()), bytes(server_kp.sign_message(message)), message))
+        remaining_accounts.append(AccountMeta(pubkey=player_pda, is_signer=False, is_writable=False))
+        remaining_accounts.append(AccountMeta(pubkey=state_pda, is_signer=False, is_writable=True))
 
     try:
-        token_pid = SPL_TOKEN_PROGRAM_ID
-
         tx_sig = await program.rpc["submit_minting_list"](
             game_number,
-            player_ids,
+            attested,
             ctx=Context(
                 accounts={
-                    "game":           game_pda,
-                    "validator_pda":  validator_pda,
-                    "validator":      validator_kp.pubkey(),
-                    "fancy_mint":     fancy_mint,
-                    "dapp":           dapp_pda,
-                    "mint_authority": mint_auth_pda,
-                    "token_program":  token_pid,
+                    "game":         game_pda,
+                    "validator":    validator_kp.pubkey(),
+                    "metadata":     metadata_pda,
+                    "instructions": SYSVAR_INSTRUCTIONS_ID,
                 },
                 signers=[validator_kp],
-                remaining_accounts=leftover_accounts,
+                remaining_accounts=remaining_accounts,
+                pre_instructions=[ed25519_attestations(signed)],
             ),
         )
         print(f"Minting list submitted. Tx: {tx_sig}")
     except RPCException as e:
         print(f"Error in submit_minting_list: {e}")
         traceback.print_exc()
+        raise
 
 
 async def main():
@@ -363,28 +620,29 @@ async def main():
         description = "Minimal Game Example"
         game_pda = await initialize_game(program, client, game_number, description, dapp_pda)
 
-        # 4) Register a validator
+        # 4) Register and stake two validators; an agreement needs two approvals
         def load_keypair(path: str) -> Keypair:
             with Path(path).open() as f:
                 secret = json.load(f)
             return Keypair.from_bytes(bytes(secret[0:64]))
 
-        validator_kp = load_keypair("./val1-keypair.json")
-        validator_pda = await register_validator_pda(
-            program=program,
-            client=client,
-            game_pda=game_pda,
-            game_number=game_number,
-            validator_kp=validator_kp,
-            fancy_mint=mint_for_dapp_pda,
-            dapp_pda=dapp_pda
-        )
-
-        # 5) Punch in as validator
-        await punch_in(program, game_pda, game_number, validator_kp, validator_pda)  # Pass validator_pda
+        second_kp = Keypair()
+        airdrop = await client.request_airdrop(second_kp.pubkey(), 2 * MIN_VALIDATOR_STAKE)
+        await client.confirm_transaction(airdrop.value, commitment=Confirmed)
+
+        validators = []
+        for validator_kp in [load_keypair("./val1-keypair.json"), second_kp]:
+            validator_pda = await register_validator_pda(
+                program=program,
+                client=client,
+                game_pda=game_pda,
+                game_number=game_number,
+                validator_kp=validator_kp,
+            )
+            await stake_validator(program, game_pda, validator_kp, validator_pda, MIN_VALIDATOR_STAKE)
+            validators.append((validator_kp, validator_pda))
 
-        # 6) Register a new Player
-        player_kp = load_keypair("./player-keypair.json")
+        # 5) Register a new Player
         # No need to specify reward_address; it will be set to user_ata
         await register_player_pda(
             program, client, dapp_pda,
@@ -392,35 +650,75 @@ async def main():
             fancy_mint=mint_for_dapp_pda  # Pass the mint pubkey here
         )
 
-        # 7) Suppose we create a dummy ATA for the "Alice" player:
-        dummy_player_ata = find_associated_token_address(Pubkey.from_string("DummyPlayerPubkeyHere"), mint_for_dapp_pda)  # Replace with actual Pubkey
+        # 6) Register a game server to sign attestations, and give Alice a state in the game
+        server_kp = Keypair()
+        metadata_pda = await register_game_server(program, dapp_pda, game_pda, game_number, server_kp)
 
         # For simplicity, let's guess the new PlayerPda is index 0
         alice_pda, _ = Pubkey.find_program_address(
             [b"player_pda", (0).to_bytes(4, "little")],
             program_id
         )
+        alice_state = await init_player_game_state(program, game_pda, game_number, 0)
 
-        # 8) Now submit a single player's leftover => [alice_pda, dummy_player_ata]
-        #   We must pass dapp_pda as well
-        await submit_minting_list(
-            program,
-            dapp_pda=dapp_pda,          # Pass in the known DApp
-            game_pda=game_pda,
-            game_number=game_number,
-            validator_kp=validator_kp,
-            validator_pda=validator_pda,
-            fancy_mint=mint_for_dapp_pda,
-            mint_auth_pda=mint_auth_pda,
-            leftover_player_pda=alice_pda,
-            leftover_player_ata=dummy_player_ata,
-        )
+        # 7) Seed round: short epochs, so commit, reveal and finalize take about a minute
+        punch_in_length, reveal_length = 20, 20
+        await configure_game_epochs(program, dapp_pda, game_pda, game_number, 120, punch_in_length, reveal_length)
+        secrets = []
+        for validator_kp, validator_pda in validators:
+            secrets.append(await punch_in(program, game_pda, game_number, validator_kp, validator_pda))
+
+        await wait_for_epoch_offset(program, client, game_pda, punch_in_length)
+        for (validator_kp, validator_pda), seed_secret in zip(validators, secrets):
+            await reveal_seed(program, game_pda, game_number, validator_kp, validator_pda, seed_secret)
+
+        await wait_for_epoch_offset(program, client, game_pda, punch_in_length + reveal_length)
+        await finalize_seed(program, game_pda, game_number)
+
+        # 8) Mint: both validators submit Alice with a server attestation for this epoch.
+        # An attestation from a key that isn't a registered server is refused first.
+        epoch = await current_epoch(program, client, game_pda)
+        try:
+            await submit_minting_list(
+                program,
+                game_pda=game_pda,
+                game_number=game_number,
+                epoch=epoch,
+                metadata_pda=metadata_pda,
+                validator_kp=validators[0][0],
+                validator_pda=validators[0][1],
+                server_kp=Keypair(),
+                players=[("Alice", 100, alice_pda, alice_state)],
+            )
+        except RPCException:
+            print("Unregistered server's attestation rejected, as expected.")
+        else:
+            raise AssertionError("submit_minting_list accepted an attestation from an unregistered server")
+
+        for validator_kp, validator_pda in validators:
+            await submit_minting_list(
+                program,
+                game_pda=game_pda,
+                game_number=game_number,
+                epoch=epoch,
+                metadata_pda=metadata_pda,
+                validator_kp=validator_kp,
+                validator_pda=validator_pda,
+                server_kp=server_kp,
+                players=[("Alice", 100, alice_pda, alice_state)],
+            )
+
+        game_data = await program.account["Game"].fetch(game_pda)
+        print(f"Pending payouts: {game_data.pending_payouts}")
+        payout_names = [payout.player_name for payout in game_data.pending_payouts]
+        assert payout_names == ["Alice"], f"expected Alice's payout to be pending, got {payout_names}"
 
         print("\nAll tests completed successfully.")
 
     except Exception as e:
         print(f"An unexpected error occurred.\n{e}")
         traceback.print_exc()
+        raise
     finally:
         await client.close()
         print("Closed Solana RPC client.")
@@ -0,0 +1,120 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+use crate::fancoin::DEFAULT_PROGRAM_ID;
+
+/// Daemon settings, read from a TOML file. See `validator.example.toml`.
+#[derive(Debug, Deserialize)]
+pub struct Config {
+    pub rpc_url: String,
+    pub keypair_path: PathBuf,
+    #[serde(default = "default_program_id")]
+    pub program_id: String,
+    pub game_number: u32,
+    pub feed: FeedConfig,
+    #[serde(default = "default_poll_interval")]
+    pub poll_interval_secs: u64,
+    /// Players per `submit_minting_list` transaction. Each carries a ~150 byte
+    /// attestation, so a 1232 byte transaction fits only a few.
+    #[serde(default = "default_max_batch")]
+    pub max_batch: usize,
+    /// Attempts per transaction when its blockhash expires
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Stop after this many epochs; runs forever when unset
+    #[serde(default)]
+    pub max_epochs: Option<u64>,
+}
+
+/// Where game-server attestations come from.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum FeedConfig {
+    /// A file the game server appends one JSON attestation per line to
+    File { path: PathBuf },
+    /// A URL returning a JSON array of attestations
+    Http { url: String },
+}
+
+impl Config {
+    pub fn load(path: &Path) -> anyhow::Result<Config> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading config {}", path.display()))?;
+        Config::parse(&text).with_context(|| format!("parsing config {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Config> {
+        let config: Config = toml::from_str(text)?;
+        anyhow::ensure!(config.max_batch > 0, "max_batch must be at least 1");
+        anyhow::ensure!(config.max_retries > 0, "max_retries must be at least 1");
+        Ok(config)
+    }
+}
+
+fn default_program_id() -> String {
+    DEFAULT_PROGRAM_ID.to_string()
+}
+
+fn default_poll_interval() -> u64 {
+    5
+}
+
+fn default_max_batch() -> usize {
+    3
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINIMAL: &str = r#"
+        rpc_url = "http://127.0.0.1:8899"
+        keypair_path = "val.json"
+        game_number = 3
+
+        [feed]
+        kind = "file"
+        path = "players.txt"
+    "#;
+
+    #[test]
+    fn fills_in_defaults() {
+        let config = Config::parse(MINIMAL).unwrap();
+        assert_eq!(config.game_number, 3);
+        assert_eq!(config.program_id, DEFAULT_PROGRAM_ID);
+        assert_eq!(config.poll_interval_secs, 5);
+        assert_eq!(config.max_batch, 3);
+        assert_eq!(config.max_retries, 5);
+        assert_eq!(config.max_epochs, None);
+        assert!(matches!(config.feed, FeedConfig::File { ref path } if path == Path::new("players.txt")));
+    }
+
+    #[test]
+    fn parses_http_feed() {
+        let text = MINIMAL.replace(r#"kind = "file""#, r#"kind = "http""#).replace(
+            r#"path = "players.txt""#,
+            r#"url = "http://127.0.0.1:8080/players""#,
+        );
+        let config = Config::parse(&text).unwrap();
+        assert!(matches!(config.feed, FeedConfig::Http { ref url } if url == "http://127.0.0.1:8080/players"));
+    }
+
+    #[test]
+    fn parses_example() {
+        let config = Config::parse(include_str!("../validator.example.toml")).unwrap();
+        assert_eq!(config.max_epochs, Some(2));
+    }
+
+    #[test]
+    fn rejects_bad_settings() {
+        assert!(Config::parse(&format!("max_batch = 0\n{}", MINIMAL)).is_err());
+        assert!(Config::parse(&format!("max_retries = 0\n{}", MINIMAL)).is_err());
+        assert!(Config::parse(&MINIMAL.replace(r#"kind = "file""#, r#"kind = "ftp""#)).is_err());
+        assert!(Config::parse(&MINIMAL.replace("game_number = 3", "")).is_err());
+    }
+}
@@ -0,0 +1,463 @@
+use anyhow::{anyhow, bail, Context};
+use sha3::{Digest, Keccak256};
+use solana_client::{
+    client_error::ClientError,
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{
+    account::from_account,
+    clock::Clock,
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signature},
+    signer::Signer,
+    sysvar,
+    transaction::{Transaction, TransactionError},
+};
+use solana_account_decoder::UiAccountEncoding;
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::fancoin::{self, GameHeader, PlayerHeader, PlayerKey, ValidatorPdaAccount};
+use crate::feed::{self, Attestation, PlayerFeed};
+
+/// Drives one validator through each epoch of one game: commit during punch-in,
+/// reveal during the reveal window, then submit the players attested on the feed and
+/// run the per-epoch cranks: paying out agreements whose challenge window has passed
+/// and pruning expired ones.
+pub struct Daemon {
+    config: Config,
+    rpc: RpcClient,
+    keypair: Keypair,
+    program_id: Pubkey,
+    feed: Box<dyn PlayerFeed>,
+    /// PlayerPda index and id by name, refreshed when an unknown name shows up
+    players: HashMap<String, PlayerKey>,
+    /// Attestations from the feed not yet submitted, at most one per name and epoch
+    pending: Vec<Attestation>,
+    /// Names already submitted this epoch
+    submitted: HashSet<String>,
+    current_epoch: Option<u64>,
+    /// Epoch in which the `release_payouts` and `prune_expired_agreements` cranks last ran
+    cranked_epoch: Option<u64>,
+    epochs_seen: u64,
+}
+
+impl Daemon {
+    pub fn new(config: Config) -> anyhow::Result<Daemon> {
+        let keypair = read_keypair_file(&config.keypair_path)
+            .map_err(|err| anyhow!("reading keypair {}: {}", config.keypair_path.display(), err))?;
+        let program_id = Pubkey::from_str(&config.program_id).context("parsing program_id")?;
+        let rpc = RpcClient::new_with_commitment(config.rpc_url.clone(), CommitmentConfig::confirmed());
+        let feed = feed::from_config(&config.feed);
+
+        Ok(Daemon {
+            config,
+            rpc,
+            keypair,
+            program_id,
+            feed,
+            players: HashMap::new(),
+            pending: Vec::new(),
+            submitted: HashSet::new(),
+            current_epoch: None,
+            cranked_epoch: None,
+            epochs_seen: 0,
+        })
+    }
+
+    /// Tick until `max_epochs` have passed, or forever. Errors in one tick are logged
+    /// and the next tick tries again.
+    pub fn run(&mut self) -> anyhow::Result<()> {
+        println!(
+            "Validator {} on game {} ({})",
+            self.keypair.pubkey(),
+            self.config.game_number,
+            self.config.rpc_url
+        );
+        loop {
+            if let Err(err) = self.tick() {
+                eprintln!("tick failed: {:#}", err);
+            }
+            if let Some(max) = self.config.max_epochs {
+                if self.epochs_seen > max {
+                    println!("Done after {} epochs", max);
+                    return Ok(());
+                }
+            }
+            std::thread::sleep(Duration::from_secs(self.config.poll_interval_secs));
+        }
+    }
+
+    fn tick(&mut self) -> anyhow::Result<()> {
+        let game = self.fetch_game()?;
+        let now = self.cluster_time()?;
+        let (epoch, offset) = game
+            .epoch_at(now)
+            .ok_or_else(|| anyhow!("game {} has not started", self.config.game_number))?;
+
+        if self.current_epoch != Some(epoch) {
+            println!("Epoch {}", epoch);
+            self.current_epoch = Some(epoch);
+            self.epochs_seen += 1;
+            self.submitted.clear();
+            self.pending.retain(|attestation| attestation.epoch >= epoch);
+        }
+
+        let in_round = offset < game.punch_in_length + game.reveal_length;
+        if offset < game.punch_in_length {
+            let val_pda = self.fetch_validator_pda()?;
+            if val_pda.commitment_epoch != Some(epoch) {
+                let secret = self.epoch_secret(epoch);
+                let commitment = fancoin::seed_commitment(&secret, &self.keypair.pubkey());
+                let ix = fancoin::punch_in(&self.program_id, self.config.game_number, &self.keypair.pubkey(), commitment);
+                let sig = self.send(&[ix])?;
+                println!("Punched in for epoch {}: {}", epoch, sig);
+            }
+        } else if in_round {
+            let val_pda = self.fetch_validator_pda()?;
+            if val_pda.commitment_epoch == Some(epoch) && val_pda.seed_commitment.is_some() {
+                let secret = self.epoch_secret(epoch);
+                let ix = fancoin::reveal_seed(&self.program_id, self.config.game_number, &self.keypair.pubkey(), secret);
+                let sig = self.send(&[ix])?;
+                println!("Revealed for epoch {}: {}", epoch, sig);
+            }
+        }
+
+        // Polled only after the commit-reveal round is served, so an unreachable feed
+        // can't make the validator miss it
+        match self.feed.poll() {
+            Ok(polled) => queue_attestations(&mut self.pending, &self.submitted, epoch, polled),
+            Err(err) => eprintln!("feed poll failed: {:#}", err),
+        }
+
+        if !in_round {
+            if self.cranked_epoch != Some(epoch) {
+                // Once per epoch is enough; other validators crank too
+                self.cranked_epoch = Some(epoch);
+                let release = fancoin::release_payouts(&self.program_id, self.config.game_number);
+                let prune = fancoin::prune_expired_agreements(&self.program_id, self.config.game_number);
+                match self.send(&[release, prune]) {
+                    Ok(sig) => println!("Released payouts and pruned agreements for epoch {}: {}", epoch, sig),
+                    Err(err) => eprintln!("epoch cranks: {:#}", err),
+                }
+            }
+            if game.seed_epoch == Some(epoch) && game.last_seed.is_none() {
+                // Another validator may win this race; that's fine. It also fails until
+                // the seed slot has passed, so it's retried on the next tick.
+                let ix = fancoin::finalize_seed(&self.program_id, self.config.game_number);
+                match self.send(&[ix]) {
+                    Ok(sig) => println!("Finalized seed for epoch {}: {}", epoch, sig),
+                    Err(err) => eprintln!("finalize_seed: {:#}", err),
+                }
+                return Ok(());
+            }
+            if game.seed_ready(epoch) {
+                self.submit_pending(epoch)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Submit this epoch's pending attestations in batches of `max_batch`, creating any
+    /// missing `PlayerGameState` first. Each batch goes out as the ed25519 check of its
+    /// signatures followed by `submit_minting_list`. Attestations for unknown players or
+    /// with a bad signature are dropped.
+    fn submit_pending(&mut self, epoch: u64) -> anyhow::Result<()> {
+        let (due, later): (Vec<Attestation>, Vec<Attestation>) =
+            std::mem::take(&mut self.pending).into_iter().partition(|a| a.epoch == epoch);
+        self.pending = later;
+        if due.is_empty() {
+            return Ok(());
+        }
+        if due.iter().any(|a| !self.players.contains_key(&a.player_name)) {
+            self.refresh_players()?;
+        }
+
+        let mut batch = Vec::new();
+        for attestation in due {
+            let Some(key) = self.players.get(&attestation.player_name).copied() else {
+                eprintln!("No PlayerPda named {:?}; skipping", attestation.player_name);
+                continue;
+            };
+            match self.signed_message(&attestation) {
+                Ok(signed) => batch.push((key, attestation, signed)),
+                Err(err) => eprintln!("Bad attestation for {:?}: {:#}", attestation.player_name, err),
+            }
+        }
+
+        let mut batches = batch.chunks(self.config.max_batch);
+        while let Some(chunk) = batches.next() {
+            let players: Vec<(PlayerKey, fancoin::AttestedPlayer)> = chunk
+                .iter()
+                .map(|(key, attestation, _)| {
+                    let player = fancoin::AttestedPlayer {
+                        player_name: attestation.player_name.clone(),
+                        activity_score: attestation.activity_score,
+                    };
+                    (*key, player)
+                })
+                .collect();
+            let signed: Vec<_> = chunk.iter().map(|(_, _, signed)| signed.clone()).collect();
+            let result = self.ensure_player_states(&players).and_then(|_| {
+                let verify = fancoin::ed25519_attestations(&signed);
+                let submit = fancoin::submit_minting_list(
+                    &self.program_id,
+                    self.config.game_number,
+                    &self.keypair.pubkey(),
+                    &players,
+                );
+                self.send(&[verify, submit])
+            });
+            match result {
+                Ok(sig) => {
+                    println!("Submitted {} players: {}", chunk.len(), sig);
+                    self.submitted.extend(players.into_iter().map(|(_, player)| player.player_name));
+                }
+                Err(err) => {
+                    // indexes shift when players deregister, so reload them next time
+                    self.players.clear();
+                    // keep the rest for the next tick
+                    self.pending.extend(chunk.iter().map(|(_, attestation, _)| attestation.clone()));
+                    self.pending.extend(batches.flatten().map(|(_, attestation, _)| attestation.clone()));
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The (server key, signature, message) the ed25519 program will check for
+    /// `attestation`, verified here first so one bad entry can't sink a batch.
+    fn signed_message(&self, attestation: &Attestation) -> anyhow::Result<([u8; 32], [u8; 64], Vec<u8>)> {
+        let key = Pubkey::from_str(&attestation.server_key).context("parsing server_key")?;
+        let signature = Signature::from_str(&attestation.signature).context("parsing signature")?;
+        let message = fancoin::attestation_message(
+            self.config.game_number,
+            attestation.epoch,
+            &attestation.player_name,
+            attestation.activity_score,
+        );
+        anyhow::ensure!(signature.verify(key.as_ref(), &message), "signature does not verify");
+        let signature: [u8; 64] = signature.as_ref().try_into()?;
+        Ok((key.to_bytes(), signature, message))
+    }
+
+    fn ensure_player_states(&self, players: &[(PlayerKey, fancoin::AttestedPlayer)]) -> anyhow::Result<()> {
+        let keys: Vec<Pubkey> = players
+            .iter()
+            .map(|(key, _)| fancoin::player_game_state_pda(&self.program_id, self.config.game_number, key.id))
+            .collect();
+        let missing: Vec<Instruction> = self
+            .rpc
+            .get_multiple_accounts(&keys)?
+            .iter()
+            .zip(players)
+            .filter(|(account, _)| account.is_none())
+            .map(|(_, (key, _))| {
+                fancoin::init_player_game_state(&self.program_id, self.config.game_number, *key, &self.keypair.pubkey())
+            })
+            .collect();
+        if !missing.is_empty() {
+            let sig = self.send(&missing)?;
+            println!("Created {} player game states: {}", missing.len(), sig);
+        }
+        Ok(())
+    }
+
+    /// Load every `PlayerPda` of the program into the name index.
+    fn refresh_players(&mut self) -> anyhow::Result<()> {
+        let filter = RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            0,
+            fancoin::account_discriminator("PlayerPda").to_vec(),
+        ));
+        let accounts = self.rpc.get_program_accounts_with_config(
+            &self.program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![filter]),
+                account_config: RpcAccountInfoConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    ..RpcAccountInfoConfig::default()
+                },
+                ..RpcProgramAccountsConfig::default()
+            },
+        )?;
+        self.players.clear();
+        for (_, account) in accounts {
+            let player: PlayerHeader = fancoin::decode("PlayerPda", &account.data)?;
+            self.players.insert(player.name, PlayerKey { index: player.index, id: player.id });
+        }
+        Ok(())
+    }
+
+    fn fetch_game(&self) -> anyhow::Result<GameHeader> {
+        let key = fancoin::game_pda(&self.program_id, self.config.game_number);
+        let account = self.rpc.get_account(&key).context("fetching game")?;
+        fancoin::decode("Game", &account.data)
+    }
+
+    fn fetch_validator_pda(&self) -> anyhow::Result<ValidatorPdaAccount> {
+        let key = fancoin::validator_pda(&self.program_id, self.config.game_number, &self.keypair.pubkey());
+        let account = self
+            .rpc
+            .get_account(&key)
+            .context("fetching ValidatorPda; is this validator registered for the game?")?;
+        fancoin::decode("ValidatorPda", &account.data)
+    }
+
+    fn cluster_time(&self) -> anyhow::Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID)?;
+        let clock: Clock = from_account(&account).ok_or_else(|| anyhow!("bad Clock sysvar"))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    /// The secret committed to for `epoch`. Derived from a signature, which ed25519
+    /// makes deterministic, so a restarted daemon can still reveal.
+    fn epoch_secret(&self, epoch: u64) -> [u8; 32] {
+        let mut message = b"fancoin-seed".to_vec();
+        message.extend(self.config.game_number.to_le_bytes());
+        message.extend(epoch.to_le_bytes());
+        let signature = self.keypair.sign_message(&message);
+        Keccak256::digest(signature.as_ref()).into()
+    }
+
+    /// Sign and send `instructions`, starting over with a fresh blockhash when the
+    /// previous one expired before the transaction landed.
+    fn send(&self, instructions: &[Instruction]) -> anyhow::Result<Signature> {
+        for attempt in 1..=self.config.max_retries {
+            match self.send_once(instructions)? {
+                Attempt::Landed(sig) => return Ok(sig),
+                Attempt::Expired => {
+                    eprintln!("blockhash expired (attempt {}/{}); retrying", attempt, self.config.max_retries);
+                }
+            }
+        }
+        bail!("transaction not confirmed after {} attempts", self.config.max_retries)
+    }
+
+    /// Send `instructions` under the latest blockhash and wait until they land or the
+    /// blockhash stops being valid. Confirms by hand because `send_and_confirm_transaction`
+    /// reports an expired blockhash only as a message string.
+    fn send_once(&self, instructions: &[Instruction]) -> anyhow::Result<Attempt> {
+        let blockhash = self.rpc.get_latest_blockhash()?;
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.keypair.pubkey()),
+            &[&self.keypair],
+            blockhash,
+        );
+        let signature = match self.rpc.send_transaction(&tx) {
+            Ok(signature) => signature,
+            Err(err) if is_blockhash_expired(&err) => return Ok(Attempt::Expired),
+            Err(err) => return Err(err.into()),
+        };
+        loop {
+            match self.rpc.get_signature_status(&signature)? {
+                Some(result) => {
+                    result?;
+                    return Ok(Attempt::Landed(signature));
+                }
+                None if !self.rpc.is_blockhash_valid(&blockhash, CommitmentConfig::processed())? => {
+                    return Ok(Attempt::Expired);
+                }
+                None => std::thread::sleep(Duration::from_millis(500)),
+            }
+        }
+    }
+}
+
+/// Outcome of one `Daemon::send_once`.
+enum Attempt {
+    Landed(Signature),
+    /// The blockhash expired first; the transaction can no longer land
+    Expired,
+}
+
+/// Add `polled` to `pending`, dropping past epochs, names already submitted this
+/// epoch and repeats of an attestation that is already pending.
+fn queue_attestations(pending: &mut Vec<Attestation>, submitted: &HashSet<String>, epoch: u64, polled: Vec<Attestation>) {
+    for attestation in polled {
+        if attestation.epoch < epoch
+            || (attestation.epoch == epoch && submitted.contains(&attestation.player_name))
+            || pending.iter().any(|queued| {
+                queued.epoch == attestation.epoch && queued.player_name == attestation.player_name
+            })
+        {
+            continue;
+        }
+        pending.push(attestation);
+    }
+}
+
+/// Whether the cluster rejected a transaction for its blockhash, either directly or in
+/// the preflight simulation.
+fn is_blockhash_expired(err: &ClientError) -> bool {
+    matches!(err.get_transaction_error(), Some(TransactionError::BlockhashNotFound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attestation(name: &str, epoch: u64) -> Attestation {
+        Attestation {
+            player_name: name.to_string(),
+            epoch,
+            activity_score: 1,
+            server_key: String::new(),
+            signature: String::new(),
+        }
+    }
+
+    #[test]
+    fn queues_each_player_once_per_epoch() {
+        let mut pending = vec![attestation("alice", 5)];
+        let submitted = HashSet::from(["bob".to_string()]);
+        let polled = vec![
+            attestation("old", 4),
+            attestation("alice", 5),
+            attestation("bob", 5),
+            attestation("bob", 6),
+            attestation("carol", 5),
+            attestation("carol", 5),
+        ];
+        queue_attestations(&mut pending, &submitted, 5, polled);
+
+        let queued: Vec<_> = pending.iter().map(|a| (a.player_name.as_str(), a.epoch)).collect();
+        assert_eq!(queued, [("alice", 5), ("bob", 6), ("carol", 5)]);
+    }
+
+    #[test]
+    fn only_a_missing_blockhash_counts_as_expired() {
+        use solana_client::client_error::ClientErrorKind;
+        use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+        use solana_client::rpc_response::RpcSimulateTransactionResult;
+
+        let preflight = |err: TransactionError| -> ClientError {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code: -32002,
+                message: "Transaction simulation failed".to_string(),
+                data: RpcResponseErrorData::SendTransactionPreflightFailure(RpcSimulateTransactionResult {
+                    err: Some(err),
+                    logs: None,
+                    accounts: None,
+                    units_consumed: None,
+                    return_data: None,
+                    inner_instructions: None,
+                }),
+            })
+            .into()
+        };
+        assert!(is_blockhash_expired(&preflight(TransactionError::BlockhashNotFound)));
+        assert!(is_blockhash_expired(&ClientErrorKind::TransactionError(TransactionError::BlockhashNotFound).into()));
+        assert!(!is_blockhash_expired(&preflight(TransactionError::AccountNotFound)));
+        // Messages that merely mention a blockhash are not an expiry
+        let message = ClientErrorKind::Custom("invalid blockhash in config".to_string()).into();
+        assert!(!is_blockhash_expired(&message));
+    }
+}
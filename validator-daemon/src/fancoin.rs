@@ -0,0 +1,379 @@
+//! Client side of the fancoin program (programs/hip/src/lib3.rs): PDAs, instruction
+//! builders and the parts of its accounts the daemon reads. Kept in step with the program
+//! by hand, since lib3.rs is not built as a crate this one could depend on.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use sha3::{Digest, Keccak256};
+use solana_sdk::{
+    ed25519_program,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+pub const DEFAULT_PROGRAM_ID: &str = "HP9ucKGU9Sad7EaWjrGULC2ZSyYD1ScxVPh15QmdRmut";
+
+// ------------------------------------------------------------------
+// PDAs
+// ------------------------------------------------------------------
+
+pub fn game_pda(program_id: &Pubkey, game_number: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"game", &game_number.to_le_bytes()], program_id).0
+}
+
+pub fn validator_pda(program_id: &Pubkey, game_number: u32, validator: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"validator", &game_number.to_le_bytes(), validator.as_ref()],
+        program_id,
+    )
+    .0
+}
+
+pub fn game_metadata_pda(program_id: &Pubkey, game_number: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"game_metadata", &game_number.to_le_bytes()], program_id).0
+}
+
+pub fn player_pda(program_id: &Pubkey, index: u32) -> Pubkey {
+    Pubkey::find_program_address(&[b"player_pda", &index.to_le_bytes()], program_id).0
+}
+
+pub fn player_game_state_pda(program_id: &Pubkey, game_number: u32, player_id: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"player_game", &game_number.to_le_bytes(), &player_id.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Same as the program's `attestation_message`.
+pub fn attestation_message(game_number: u32, epoch: u64, player_name: &str, activity_score: u64) -> Vec<u8> {
+    let mut message = b"fancoin-attestation".to_vec();
+    message.extend_from_slice(&game_number.to_le_bytes());
+    message.extend_from_slice(&epoch.to_le_bytes());
+    message.extend_from_slice(&activity_score.to_le_bytes());
+    message.extend_from_slice(player_name.as_bytes());
+    message
+}
+
+/// Same as the program's `seed_commitment`: keccak(secret || validator).
+pub fn seed_commitment(secret: &[u8; 32], validator: &Pubkey) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(secret);
+    hasher.update(validator.to_bytes());
+    hasher.finalize().into()
+}
+
+// ------------------------------------------------------------------
+// Instructions
+// ------------------------------------------------------------------
+
+fn instruction_data(name: &str, args: impl BorshSerialize) -> Vec<u8> {
+    let mut data = hashv(&[b"global:", name.as_bytes()]).to_bytes()[..8].to_vec();
+    data.extend(args.try_to_vec().expect("borsh serialization into a Vec cannot fail"));
+    data
+}
+
+pub fn punch_in(program_id: &Pubkey, game_number: u32, validator: &Pubkey, commitment: [u8; 32]) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("punch_in", (game_number, commitment)),
+        vec![
+            AccountMeta::new(game_pda(program_id, game_number), false),
+            AccountMeta::new(validator_pda(program_id, game_number, validator), false),
+            AccountMeta::new(*validator, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+pub fn reveal_seed(program_id: &Pubkey, game_number: u32, validator: &Pubkey, secret: [u8; 32]) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("reveal_seed", (game_number, secret)),
+        vec![
+            AccountMeta::new(game_pda(program_id, game_number), false),
+            AccountMeta::new(validator_pda(program_id, game_number, validator), false),
+            AccountMeta::new_readonly(*validator, true),
+        ],
+    )
+}
+
+pub fn finalize_seed(program_id: &Pubkey, game_number: u32) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("finalize_seed", game_number),
+        vec![
+            AccountMeta::new(game_pda(program_id, game_number), false),
+            AccountMeta::new_readonly(sysvar::slot_hashes::ID, false),
+        ],
+    )
+}
+
+pub fn release_payouts(program_id: &Pubkey, game_number: u32) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("release_payouts", game_number),
+        vec![AccountMeta::new(game_pda(program_id, game_number), false)],
+    )
+}
+
+pub fn prune_expired_agreements(program_id: &Pubkey, game_number: u32) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("prune_expired_agreements", game_number),
+        vec![AccountMeta::new(game_pda(program_id, game_number), false)],
+    )
+}
+
+/// A player's current `PlayerPda` index and its stable id.
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerKey {
+    pub index: u32,
+    pub id: u64,
+}
+
+pub fn init_player_game_state(program_id: &Pubkey, game_number: u32, player: PlayerKey, payer: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("init_player_game_state", (game_number, player.index)),
+        vec![
+            AccountMeta::new_readonly(game_pda(program_id, game_number), false),
+            AccountMeta::new(player_pda(program_id, player.index), false),
+            AccountMeta::new(player_game_state_pda(program_id, game_number, player.id), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(system_program::ID, false),
+        ],
+    )
+}
+
+/// Argument of `submit_minting_list`, mirroring the program's `AttestedPlayer`.
+#[derive(BorshSerialize, Clone, Debug)]
+pub struct AttestedPlayer {
+    pub player_name: String,
+    pub activity_score: u64,
+}
+
+/// `players` are (player key, player). The remaining accounts are the validator's own
+/// `ValidatorPda`, then each player's `PlayerPda` and writable `PlayerGameState`.
+/// Must follow an `ed25519_attestations` instruction covering every player.
+pub fn submit_minting_list(
+    program_id: &Pubkey,
+    game_number: u32,
+    validator: &Pubkey,
+    players: &[(PlayerKey, AttestedPlayer)],
+) -> Instruction {
+    let args: Vec<AttestedPlayer> = players.iter().map(|(_, player)| player.clone()).collect();
+    let mut accounts = vec![
+        AccountMeta::new(game_pda(program_id, game_number), false),
+        AccountMeta::new_readonly(*validator, true),
+        AccountMeta::new_readonly(game_metadata_pda(program_id, game_number), false),
+        AccountMeta::new_readonly(sysvar::instructions::ID, false),
+        AccountMeta::new(validator_pda(program_id, game_number, validator), false),
+    ];
+    for (key, _) in players {
+        accounts.push(AccountMeta::new_readonly(player_pda(program_id, key.index), false));
+        accounts.push(AccountMeta::new(player_game_state_pda(program_id, game_number, key.id), false));
+    }
+    Instruction::new_with_bytes(
+        *program_id,
+        &instruction_data("submit_minting_list", (game_number, args)),
+        accounts,
+    )
+}
+
+/// One ed25519 precompile instruction checking every (server key, signature, message).
+/// All offsets point into this instruction, as `submit_minting_list` requires.
+pub fn ed25519_attestations(signed: &[([u8; 32], [u8; 64], Vec<u8>)]) -> Instruction {
+    const HEADER_LEN: usize = 2;
+    const OFFSETS_LEN: usize = 14;
+    const THIS_INSTRUCTION: u16 = u16::MAX;
+
+    let mut data = vec![signed.len() as u8, 0];
+    let mut payload = Vec::new();
+    let payload_start = HEADER_LEN + signed.len() * OFFSETS_LEN;
+    for (key, signature, message) in signed {
+        let key_offset = payload_start + payload.len();
+        payload.extend_from_slice(key);
+        let signature_offset = payload_start + payload.len();
+        payload.extend_from_slice(signature);
+        let message_offset = payload_start + payload.len();
+        payload.extend_from_slice(message);
+
+        for value in [
+            signature_offset as u16,
+            THIS_INSTRUCTION,
+            key_offset as u16,
+            THIS_INSTRUCTION,
+            message_offset as u16,
+            message.len() as u16,
+            THIS_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    data.extend(payload);
+    Instruction::new_with_bytes(ed25519_program::ID, &data, vec![])
+}
+
+// ------------------------------------------------------------------
+// Accounts
+// ------------------------------------------------------------------
+
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let mut disc = [0u8; 8];
+    disc.copy_from_slice(&hashv(&[b"account:", name.as_bytes()]).to_bytes()[..8]);
+    disc
+}
+
+/// Decode the leading fields of an Anchor account named `name`; trailing fields are ignored.
+pub fn decode<T: BorshDeserialize>(name: &str, data: &[u8]) -> anyhow::Result<T> {
+    anyhow::ensure!(
+        data.len() >= 8 && data[..8] == account_discriminator(name),
+        "not a {} account",
+        name
+    );
+    Ok(T::deserialize(&mut &data[8..])?)
+}
+
+// The account mirrors below spell out every field up to the last one the daemon needs,
+// so some are only there for the layout.
+
+/// Leading fields of `Game`, through the commit-reveal round.
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct GameHeader {
+    pub game_number: u32,
+    pub validator_count: u32,
+    pub status: u8,
+    pub creator: [u8; 32],
+    pub description: String,
+    pub last_seed: Option<u64>,
+    pub last_punch_in_time: Option<i64>,
+    pub epoch_length: i64,
+    pub punch_in_length: i64,
+    pub reveal_length: i64,
+    pub epoch_start: i64,
+    pub epoch_base: u64,
+    pub seed_epoch: Option<u64>,
+}
+
+impl GameHeader {
+    /// Epoch number at `now`, and seconds elapsed since that epoch began.
+    pub fn epoch_at(&self, now: i64) -> Option<(u64, i64)> {
+        if now < self.epoch_start || self.epoch_length <= 0 {
+            return None;
+        }
+        let elapsed = now - self.epoch_start;
+        Some((self.epoch_base + (elapsed / self.epoch_length) as u64, elapsed % self.epoch_length))
+    }
+
+    /// Whether this epoch's seed is final, so minting lists are accepted.
+    pub fn seed_ready(&self, epoch: u64) -> bool {
+        self.seed_epoch == Some(epoch) && self.last_seed.is_some()
+    }
+}
+
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct ValidatorPdaAccount {
+    pub game_number: u32,
+    pub index: u32,
+    pub bump: u8,
+    pub address: [u8; 32],
+    pub last_activity: i64,
+    pub stake: u64,
+    pub failed_approvals: u32,
+    pub last_conflict_slash_epoch: Option<i64>,
+    pub seed_commitment: Option<[u8; 32]>,
+    pub commitment_epoch: Option<u64>,
+}
+
+/// Leading fields of `PlayerPda`.
+#[allow(dead_code)]
+#[derive(BorshDeserialize, Debug)]
+pub struct PlayerHeader {
+    pub index: u32,
+    pub bump: u8,
+    pub id: u64,
+    pub name: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(name: &str, index: u32, id: u64) -> (PlayerKey, AttestedPlayer) {
+        (PlayerKey { index, id }, AttestedPlayer { player_name: name.to_string(), activity_score: 5 })
+    }
+
+    #[test]
+    fn submit_minting_list_accounts() {
+        let program_id = Pubkey::new_unique();
+        let validator = Pubkey::new_unique();
+        let players = [player("alice", 0, 7), player("bob", 3, 2)];
+        let ix = submit_minting_list(&program_id, 4, &validator, &players);
+
+        let expected = [
+            (game_pda(&program_id, 4), true, false),
+            (validator, false, true),
+            (game_metadata_pda(&program_id, 4), false, false),
+            (sysvar::instructions::ID, false, false),
+            (validator_pda(&program_id, 4, &validator), true, false),
+            (player_pda(&program_id, 0), false, false),
+            (player_game_state_pda(&program_id, 4, 7), true, false),
+            (player_pda(&program_id, 3), false, false),
+            (player_game_state_pda(&program_id, 4, 2), true, false),
+        ];
+        let actual: Vec<_> = ix.accounts.iter().map(|a| (a.pubkey, a.is_writable, a.is_signer)).collect();
+        assert_eq!(actual, expected);
+
+        let mut args = &ix.data[8..];
+        let (game_number, names): (u32, Vec<(String, u64)>) = BorshDeserialize::deserialize(&mut args).unwrap();
+        assert_eq!(game_number, 4);
+        assert_eq!(names, [("alice".to_string(), 5), ("bob".to_string(), 5)]);
+        assert_eq!(ix.data[..8], hashv(&[b"global:submit_minting_list"]).to_bytes()[..8]);
+    }
+
+    #[test]
+    fn ed25519_offsets_point_into_payload() {
+        let signed = vec![([1u8; 32], [2u8; 64], b"first".to_vec()), ([3u8; 32], [4u8; 64], b"second!".to_vec())];
+        let ix = ed25519_attestations(&signed);
+        assert_eq!(ix.program_id, ed25519_program::ID);
+        assert_eq!(ix.data[0], 2);
+
+        let read = |at: usize| u16::from_le_bytes([ix.data[at], ix.data[at + 1]]) as usize;
+        for (i, (key, signature, message)) in signed.iter().enumerate() {
+            let offsets = 2 + i * 14;
+            assert_eq!(&ix.data[read(offsets)..read(offsets) + 64], signature);
+            assert_eq!(&ix.data[read(offsets + 4)..read(offsets + 4) + 32], key);
+            let (start, len) = (read(offsets + 8), read(offsets + 10));
+            assert_eq!(&ix.data[start..start + len], message.as_slice());
+            for instruction_index in [offsets + 2, offsets + 6, offsets + 12] {
+                assert_eq!(read(instruction_index), u16::MAX as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn epoch_at_counts_from_base() {
+        let game = GameHeader {
+            game_number: 1,
+            validator_count: 0,
+            status: 0,
+            creator: [0; 32],
+            description: String::new(),
+            last_seed: None,
+            last_punch_in_time: None,
+            epoch_length: 60,
+            punch_in_length: 10,
+            reveal_length: 10,
+            epoch_start: 1_000,
+            epoch_base: 5,
+            seed_epoch: None,
+        };
+        assert_eq!(game.epoch_at(999), None);
+        assert_eq!(game.epoch_at(1_000), Some((5, 0)));
+        assert_eq!(game.epoch_at(1_125), Some((7, 5)));
+    }
+}
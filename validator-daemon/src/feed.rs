@@ -0,0 +1,224 @@
+use anyhow::Context;
+use serde::Deserialize;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::config::FeedConfig;
+
+/// A game server's signed statement that a player was active in an epoch.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Attestation {
+    pub player_name: String,
+    pub epoch: u64,
+    pub activity_score: u64,
+    /// Base58 signing key, registered with `add_game_server`
+    pub server_key: String,
+    /// Base58 ed25519 signature over the program's `attestation_message`
+    pub signature: String,
+}
+
+/// Source of attestations reported by a game server.
+pub trait PlayerFeed {
+    /// Attestations published since the previous poll.
+    fn poll(&mut self) -> anyhow::Result<Vec<Attestation>>;
+}
+
+pub fn from_config(config: &FeedConfig) -> Box<dyn PlayerFeed> {
+    match config {
+        FeedConfig::File { path } => Box::new(FileFeed::new(path.clone())),
+        FeedConfig::Http { url } => Box::new(HttpFeed { url: url.clone() }),
+    }
+}
+
+/// Tails a file with one JSON attestation per line. Only complete lines are consumed.
+/// The file is read again from the start when it is replaced (rotated to a new inode)
+/// or rewritten in place, which shows as a different first line.
+pub struct FileFeed {
+    path: PathBuf,
+    offset: usize,
+    /// Device and inode of the file `offset` points into
+    inode: Option<(u64, u64)>,
+    /// First line of that file, including its newline
+    head: String,
+}
+
+impl FileFeed {
+    pub fn new(path: PathBuf) -> FileFeed {
+        FileFeed { path, offset: 0, inode: None, head: String::new() }
+    }
+
+    fn read(&self) -> std::io::Result<(String, Option<(u64, u64)>)> {
+        let mut file = std::fs::File::open(&self.path)?;
+        let inode = inode(&file.metadata()?);
+        let mut text = String::new();
+        file.read_to_string(&mut text)?;
+        Ok((text, inode))
+    }
+}
+
+#[cfg(unix)]
+fn inode(metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn inode(_metadata: &std::fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+impl PlayerFeed for FileFeed {
+    fn poll(&mut self) -> anyhow::Result<Vec<Attestation>> {
+        let (text, inode) = match self.read() {
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => {
+                return Err(err).with_context(|| format!("reading feed {}", self.path.display()))
+            }
+        };
+        if inode != self.inode || !text.starts_with(&self.head) {
+            self.offset = 0;
+            self.head.clear();
+        }
+        self.inode = inode;
+
+        let unread = match text.get(self.offset..) {
+            Some(unread) => unread,
+            None => {
+                self.offset = 0;
+                self.head.clear();
+                &text
+            }
+        };
+        let complete = match unread.rfind('\n') {
+            Some(end) => &unread[..=end],
+            None => return Ok(Vec::new()),
+        };
+        if self.offset == 0 {
+            let first_end = complete.find('\n').unwrap_or(0);
+            self.head = complete[..=first_end].to_string();
+        }
+        self.offset += complete.len();
+
+        let mut attestations = Vec::new();
+        for line in complete.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match serde_json::from_str(line) {
+                Ok(attestation) => attestations.push(attestation),
+                Err(err) => eprintln!("skipping bad feed line {:?}: {}", line, err),
+            }
+        }
+        Ok(attestations)
+    }
+}
+
+/// Polls a URL that returns a JSON array of recent attestations.
+pub struct HttpFeed {
+    url: String,
+}
+
+impl PlayerFeed for HttpFeed {
+    fn poll(&mut self) -> anyhow::Result<Vec<Attestation>> {
+        let attestations: Vec<Attestation> = ureq::get(&self.url)
+            .call()
+            .with_context(|| format!("polling feed {}", self.url))?
+            .into_json()?;
+        Ok(attestations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn line(name: &str, epoch: u64) -> String {
+        format!(
+            "{{\"player_name\":\"{}\",\"epoch\":{},\"activity_score\":1,\"server_key\":\"k\",\"signature\":\"s\"}}\n",
+            name, epoch
+        )
+    }
+
+    fn feed_file(name: &str) -> FileFeed {
+        let path = std::env::temp_dir().join(format!("fancoin-feed-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_file(&path);
+        FileFeed::new(path)
+    }
+
+    fn append(feed: &FileFeed, text: &str) {
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&feed.path).unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    fn names(attestations: Vec<Attestation>) -> Vec<String> {
+        attestations.into_iter().map(|a| a.player_name).collect()
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let mut feed = feed_file("missing");
+        assert!(feed.poll().unwrap().is_empty());
+    }
+
+    #[test]
+    fn waits_for_complete_lines() {
+        let mut feed = feed_file("partial");
+        let alice = line("alice", 1);
+        append(&feed, &alice[..10]);
+        assert!(feed.poll().unwrap().is_empty());
+
+        append(&feed, &alice[10..]);
+        append(&feed, &line("bob", 1));
+        assert_eq!(names(feed.poll().unwrap()), ["alice", "bob"]);
+        assert!(feed.poll().unwrap().is_empty());
+        std::fs::remove_file(&feed.path).unwrap();
+    }
+
+    #[test]
+    fn skips_bad_lines() {
+        let mut feed = feed_file("bad");
+        append(&feed, &format!("not json\n\n{}", line("carol", 2)));
+        assert_eq!(names(feed.poll().unwrap()), ["carol"]);
+        std::fs::remove_file(&feed.path).unwrap();
+    }
+
+    #[test]
+    fn rereads_truncated_file() {
+        let mut feed = feed_file("truncated");
+        append(&feed, &line("alice", 1));
+        append(&feed, &line("bob", 1));
+        assert_eq!(feed.poll().unwrap().len(), 2);
+
+        std::fs::write(&feed.path, line("dave", 2)).unwrap();
+        assert_eq!(names(feed.poll().unwrap()), ["dave"]);
+        std::fs::remove_file(&feed.path).unwrap();
+    }
+
+    #[test]
+    fn rereads_rotated_file() {
+        let mut feed = feed_file("rotated");
+        append(&feed, &line("alice", 1));
+        assert_eq!(feed.poll().unwrap().len(), 1);
+
+        // A longer replacement under a new inode
+        let replacement = feed.path.with_extension("new");
+        std::fs::write(&replacement, line("erin", 2) + &line("frank", 2)).unwrap();
+        std::fs::rename(&replacement, &feed.path).unwrap();
+        assert_eq!(names(feed.poll().unwrap()), ["erin", "frank"]);
+        std::fs::remove_file(&feed.path).unwrap();
+    }
+
+    #[test]
+    fn rereads_file_rewritten_in_place() {
+        let mut feed = feed_file("rewritten");
+        append(&feed, &line("é", 1));
+        assert_eq!(feed.poll().unwrap().len(), 1);
+
+        // Same inode and at least as long, but the old offset lands mid-character
+        let file = std::fs::OpenOptions::new().write(true).open(&feed.path).unwrap();
+        file.set_len(0).unwrap();
+        append(&feed, &format!("{}{}", "ü".repeat(40), line("gina", 2)));
+        append(&feed, &line("hal", 2));
+        assert_eq!(names(feed.poll().unwrap()), ["hal"]);
+        std::fs::remove_file(&feed.path).unwrap();
+    }
+}
@@ -0,0 +1,7 @@
+//! Validator daemon for the fancoin program. The binary in `main.rs` runs a `Daemon`;
+//! the modules are public so the end-to-end tests can drive a local cluster with them.
+
+pub mod config;
+pub mod daemon;
+pub mod fancoin;
+pub mod feed;
@@ -0,0 +1,15 @@
+//! Validator daemon for the fancoin program.
+//!
+//! Usage: fancoin-validator [config.toml]   (defaults to ./validator.toml)
+
+use fancoin_validator::{config, daemon};
+use std::path::PathBuf;
+
+fn main() -> anyhow::Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("validator.toml"));
+    let config = config::Config::load(&path)?;
+    daemon::Daemon::new(config)?.run()
+}
@@ -0,0 +1,306 @@
+//! End-to-end run of the daemon against a local `solana-test-validator`: set up a game
+//! with short epochs, a staked validator, a game server and a player, attest the player
+//! on the feed, run the daemon for one epoch and check it punched in, revealed,
+//! finalized the seed and submitted the player.
+//!
+//! Needs the Solana CLI (with `cargo build-sbf`) on PATH:
+//!
+//!   cargo test -p fancoin-validator --test e2e -- --ignored
+//!
+//! The program is built with `cargo build-sbf --manifest-path programs/fancoin/Cargo.toml`
+//! unless FANCOIN_PROGRAM_SO points at an already built fancoin.so.
+
+use borsh::BorshSerialize;
+use fancoin_validator::fancoin::{self, DEFAULT_PROGRAM_ID};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{write_keypair_file, Keypair},
+    sysvar,
+    signer::Signer,
+    system_program,
+    transaction::Transaction,
+};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+const RPC_URL: &str = "http://127.0.0.1:8899";
+const GAME_NUMBER: u32 = 1;
+const PLAYER_NAME: &str = "alice";
+/// `MIN_VALIDATOR_STAKE` of the program
+const STAKE: u64 = LAMPORTS_PER_SOL;
+
+/// FANCOIN_PROGRAM_SO, or the program built by `cargo build-sbf` into the workspace target.
+fn program_so() -> PathBuf {
+    if let Ok(path) = std::env::var("FANCOIN_PROGRAM_SO") {
+        return PathBuf::from(path);
+    }
+    let workspace = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+    let status = Command::new("cargo")
+        .arg("build-sbf")
+        .arg("--manifest-path")
+        .arg(workspace.join("programs/fancoin/Cargo.toml"))
+        .status()
+        .expect("running cargo build-sbf; is the Solana CLI on PATH?");
+    assert!(status.success(), "cargo build-sbf failed");
+    workspace.join("target/deploy/fancoin.so")
+}
+
+/// Kills the test validator when the test ends, pass or fail.
+struct TestValidator(Child);
+
+impl TestValidator {
+    fn start(ledger: &Path, program_so: &Path) -> TestValidator {
+        let child = Command::new("solana-test-validator")
+            .args(["--reset", "--quiet", "--ledger"])
+            .arg(ledger)
+            .args(["--bpf-program", DEFAULT_PROGRAM_ID])
+            .arg(program_so)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("starting solana-test-validator; is the Solana CLI on PATH?");
+        TestValidator(child)
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+fn wait_until_healthy(rpc: &RpcClient) {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while rpc.get_health().is_err() {
+        assert!(Instant::now() < deadline, "test validator did not come up");
+        std::thread::sleep(Duration::from_millis(500));
+    }
+}
+
+fn instruction(program_id: &Pubkey, name: &str, args: impl BorshSerialize, accounts: Vec<AccountMeta>) -> Instruction {
+    let mut data = hashv(&[b"global:", name.as_bytes()]).to_bytes()[..8].to_vec();
+    data.extend(args.try_to_vec().unwrap());
+    Instruction::new_with_bytes(*program_id, &data, accounts)
+}
+
+fn send(rpc: &RpcClient, payer: &Keypair, instructions: &[Instruction]) {
+    let blockhash = rpc.get_latest_blockhash().unwrap();
+    let tx = Transaction::new_signed_with_payer(instructions, Some(&payer.pubkey()), &[payer], blockhash);
+    rpc.send_and_confirm_transaction(&tx).unwrap();
+}
+
+/// DApp, game `GAME_NUMBER` with 40 second epochs, `validator` registered and staked for
+/// it, `server` registered as its game server and player `PLAYER_NAME`. Returns the epoch
+/// the game is in once set up.
+fn set_up_game(rpc: &RpcClient, program_id: &Pubkey, validator: &Keypair, server: &Pubkey) -> u64 {
+    let dapp = Pubkey::find_program_address(&[b"dapp"], program_id).0;
+    let game = fancoin::game_pda(program_id, GAME_NUMBER);
+    let validator_pda = fancoin::validator_pda(program_id, GAME_NUMBER, &validator.pubkey());
+    let metadata = fancoin::game_metadata_pda(program_id, GAME_NUMBER);
+    let validator_index = Pubkey::find_program_address(
+        &[b"validator_index", &GAME_NUMBER.to_le_bytes(), &0u32.to_le_bytes()],
+        program_id,
+    )
+    .0;
+    let user = validator.pubkey();
+
+    send(
+        rpc,
+        validator,
+        &[
+            instruction(
+                program_id,
+                "initialize_dapp",
+                (),
+                vec![
+                    AccountMeta::new(dapp, false),
+                    AccountMeta::new(user, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+            instruction(
+                program_id,
+                "initialize_game",
+                (GAME_NUMBER, "e2e".to_string(), user),
+                vec![
+                    AccountMeta::new(game, false),
+                    AccountMeta::new(dapp, false),
+                    AccountMeta::new(user, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+            instruction(
+                program_id,
+                "register_validator_pda",
+                GAME_NUMBER,
+                vec![
+                    AccountMeta::new(game, false),
+                    AccountMeta::new(validator_pda, false),
+                    AccountMeta::new(validator_index, false),
+                    AccountMeta::new(user, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+            instruction(
+                program_id,
+                "stake_validator",
+                STAKE,
+                vec![
+                    AccountMeta::new(validator_pda, false),
+                    AccountMeta::new(game, false),
+                    AccountMeta::new(user, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+        ],
+    );
+    send(
+        rpc,
+        validator,
+        &[
+            instruction(
+                program_id,
+                "set_game_metadata",
+                (GAME_NUMBER, "e2e".to_string(), String::new()),
+                vec![
+                    AccountMeta::new_readonly(game, false),
+                    AccountMeta::new_readonly(dapp, false),
+                    AccountMeta::new(metadata, false),
+                    AccountMeta::new(user, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+            instruction(
+                program_id,
+                "add_game_server",
+                (GAME_NUMBER, "127.0.0.1:0".to_string(), *server),
+                vec![
+                    AccountMeta::new_readonly(game, false),
+                    AccountMeta::new_readonly(dapp, false),
+                    AccountMeta::new(metadata, false),
+                    AccountMeta::new_readonly(user, true),
+                ],
+            ),
+            // First player, so PlayerPda index 0
+            instruction(
+                program_id,
+                "register_player_pda",
+                (PLAYER_NAME.to_string(), user),
+                vec![
+                    AccountMeta::new(dapp, false),
+                    AccountMeta::new(fancoin::player_pda(program_id, 0), false),
+                    AccountMeta::new(
+                        Pubkey::find_program_address(&[b"player_authority", user.as_ref()], program_id).0,
+                        false,
+                    ),
+                    AccountMeta::new(
+                        Pubkey::find_program_address(&[b"player_name", PLAYER_NAME.as_bytes()], program_id).0,
+                        false,
+                    ),
+                    AccountMeta::new_readonly(user, true),
+                    AccountMeta::new(user, true),
+                    AccountMeta::new_readonly(system_program::ID, false),
+                ],
+            ),
+        ],
+    );
+    // Last, since it starts a fresh epoch and opens its punch-in window
+    send(
+        rpc,
+        validator,
+        &[instruction(
+            program_id,
+            "configure_game_epochs",
+            (GAME_NUMBER, 40i64, 10i64, 10i64),
+            vec![
+                AccountMeta::new(game, false),
+                AccountMeta::new_readonly(dapp, false),
+                AccountMeta::new(user, true),
+            ],
+        )],
+    );
+
+    let account = rpc.get_account(&game).unwrap();
+    let header: fancoin::GameHeader = fancoin::decode("Game", &account.data).unwrap();
+    let clock = rpc.get_account(&sysvar::clock::ID).unwrap();
+    let now = solana_sdk::account::from_account::<solana_sdk::clock::Clock, _>(&clock).unwrap().unix_timestamp;
+    header.epoch_at(now).unwrap().0
+}
+
+/// One feed line in which `server` attests `PLAYER_NAME` for `epoch`.
+fn attestation_line(server: &Keypair, epoch: u64) -> String {
+    let message = fancoin::attestation_message(GAME_NUMBER, epoch, PLAYER_NAME, 1);
+    format!(
+        "{{\"player_name\":{:?},\"epoch\":{},\"activity_score\":1,\"server_key\":\"{}\",\"signature\":\"{}\"}}\n",
+        PLAYER_NAME,
+        epoch,
+        server.pubkey(),
+        server.sign_message(&message),
+    )
+}
+
+#[test]
+#[ignore = "needs solana-test-validator and cargo build-sbf"]
+fn runs_commit_reveal_round_and_submits_attested_players() {
+    let program_so = program_so();
+    let dir = std::env::temp_dir().join(format!("fancoin-e2e-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let _cluster = TestValidator::start(&dir.join("ledger"), &program_so);
+    let rpc = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
+    wait_until_healthy(&rpc);
+
+    let validator = Keypair::new();
+    let airdrop = rpc.request_airdrop(&validator.pubkey(), 10 * LAMPORTS_PER_SOL).unwrap();
+    while !rpc.confirm_transaction(&airdrop).unwrap() {
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    let program_id = Pubkey::from_str(DEFAULT_PROGRAM_ID).unwrap();
+    let server = Keypair::new();
+    let epoch = set_up_game(&rpc, &program_id, &validator, &server.pubkey());
+    let feed_path = dir.join("players.txt");
+    std::fs::write(&feed_path, attestation_line(&server, epoch)).unwrap();
+
+    let keypair_path = dir.join("validator.json");
+    write_keypair_file(&validator, &keypair_path).unwrap();
+    let config_path = dir.join("validator.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "rpc_url = {:?}\nkeypair_path = {:?}\ngame_number = {}\npoll_interval_secs = 1\nmax_epochs = 1\n\n\
+             [feed]\nkind = \"file\"\npath = {:?}\n",
+            RPC_URL,
+            keypair_path,
+            GAME_NUMBER,
+            feed_path,
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_fancoin-validator"))
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "daemon failed:\n{}\n{}", stdout, stderr);
+    for step in [
+        "Punched in for epoch",
+        "Revealed for epoch",
+        "Finalized seed for epoch",
+        "Created 1 player game states",
+        "Submitted 1 players",
+    ] {
+        assert!(stdout.contains(step), "missing {:?} in:\n{}\n{}", step, stdout, stderr);
+    }
+    let state = fancoin::player_game_state_pda(&program_id, GAME_NUMBER, 0);
+    assert!(rpc.get_account(&state).is_ok(), "no PlayerGameState for {}", PLAYER_NAME);
+    let _ = std::fs::remove_dir_all(&dir);
+}